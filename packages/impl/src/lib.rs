@@ -7,6 +7,12 @@ pub fn define(attr: TokenStream, input: TokenStream) -> TokenStream {
     transtype_lib::private::define(input.into()).into()
 }
 
+/// Implements `Wrapper` for a struct with exactly one field.
+#[proc_macro_derive(Wrapper)]
+pub fn derive_wrapper(input: TokenStream) -> TokenStream {
+    transtype_lib::private::wrapper(input.into()).into()
+}
+
 #[proc_macro]
 #[doc(hidden)]
 pub fn __predefined(input: TokenStream) -> TokenStream {
@@ -28,10 +34,16 @@ expose_macros! {
 
     pub fn transform;
 
+    pub fn attr;
+
+    pub fn convert;
+
     /// Consumes all rest tokens, generates a macro prefixes with `DEBUG_` which
     /// returns the stringified tokens tree.
     pub fn debug;
 
+    pub fn derive;
+
     pub fn extend;
 
     /// Consumes all rest tokens and returns.
@@ -39,16 +51,34 @@ expose_macros! {
 
     pub fn fork;
 
+    pub fn from;
+
+    pub fn omit;
+
+    pub fn omit_variant;
+
     pub fn rename;
 
+    pub fn rename_fields;
+
+    pub fn rename_variants;
+
     pub fn save;
 
     pub fn select;
 
     pub fn select_attr;
 
+    pub fn select_variant;
+
     pub fn resume;
 
+    pub fn try_wrapped;
+
+    pub fn unwrap;
+
+    pub fn vis;
+
     pub fn wrap;
 
     pub fn wrapped;
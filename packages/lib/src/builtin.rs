@@ -1,19 +1,28 @@
 mod ast;
+mod attr;
+mod convert;
 mod extend;
+mod rename;
 mod select;
+mod vis;
 mod wrap;
 
 use crate::{
     ExecuteState, ForkCommand, ListOf, PipeCommand, TransformInput, TransformRest, TransformState,
     Transformer,
 };
+pub(crate) use ast::DeriveInputExt;
 use ast::Nothing;
+use attr::{Attr, Derive};
+use convert::{Convert, ConvertFrom};
 use extend::Extend;
 use proc_macro2::TokenStream;
 use quote::ToTokens;
-use select::{Select, SelectAttr};
-use syn::{DeriveInput, Ident, Path, Result};
-use wrap::{Wrap, Wrapped};
+use rename::{Rename, RenameFields, RenameVariants};
+use select::{Omit, OmitVariant, Select, SelectAttr, SelectVariant};
+use syn::{DeriveInput, Path, Result};
+use vis::Vis;
+use wrap::{TryWrapped, Unwrap, Wrap, Wrapped};
 
 pub(crate) struct Executor;
 
@@ -80,17 +89,29 @@ macro_rules! builtins {
 builtins! {
     #[derive(Clone, Copy, Debug)]
     enum Cmd {
-        debug       => Debug;
-        extend      => Extend;
-        finish      => Finish;
-        fork        => Fork;
-        rename      => Rename;
-        resume      => Resume;
-        save        => Save;
-        select      => Select;
-        select_attr => SelectAttr;
-        wrap        => Wrap;
-        wrapped     => Wrapped;
+        attr            => Attr;
+        convert         => Convert;
+        debug           => Debug;
+        derive          => Derive;
+        extend          => Extend;
+        finish          => Finish;
+        fork            => Fork;
+        from            => ConvertFrom;
+        omit            => Omit;
+        omit_variant    => OmitVariant;
+        rename          => Rename;
+        rename_fields   => RenameFields;
+        rename_variants => RenameVariants;
+        resume          => Resume;
+        save            => Save;
+        select          => Select;
+        select_attr     => SelectAttr;
+        select_variant  => SelectVariant;
+        try_wrapped     => TryWrapped;
+        unwrap          => Unwrap;
+        vis             => Vis;
+        wrap            => Wrap;
+        wrapped         => Wrapped;
     }
 }
 
@@ -111,29 +132,15 @@ impl Transformer for Debug {
     type Args = TokenStream;
 
     fn transform(
-        data: DeriveInput,
+        mut data: DeriveInput,
         args: Self::Args,
         _: &mut TransformRest,
     ) -> Result<TransformState> {
+        data.remove_tracking();
         Ok(TransformState::debug(data).args(args).build())
     }
 }
 
-pub(crate) struct Rename;
-
-impl Transformer for Rename {
-    type Args = Ident;
-
-    fn transform(
-        mut data: syn::DeriveInput,
-        name: Self::Args,
-        _: &mut TransformRest,
-    ) -> Result<TransformState> {
-        data.ident = name;
-        Ok(TransformState::pipe(data).build())
-    }
-}
-
 pub(crate) struct Resume;
 
 impl Transformer for Resume {
@@ -182,10 +189,31 @@ impl Transformer for Finish {
     type Args = Nothing;
 
     fn transform(
-        data: DeriveInput,
+        mut data: DeriveInput,
         _: Self::Args,
         _: &mut TransformRest,
     ) -> Result<TransformState> {
+        data.remove_markers();
         Ok(TransformState::consume(data.into_token_stream()).build())
     }
 }
+
+mod kw {
+    use syn::custom_keyword;
+
+    custom_keyword!(attrs);
+    custom_keyword!(case);
+    custom_keyword!(default);
+    custom_keyword!(except);
+    custom_keyword!(field);
+    custom_keyword!(fields);
+    custom_keyword!(from);
+    custom_keyword!(into);
+    custom_keyword!(on_conflict);
+    custom_keyword!(only);
+    custom_keyword!(prefix);
+    custom_keyword!(strip);
+    custom_keyword!(suffix);
+    custom_keyword!(variant);
+    custom_keyword!(variants);
+}
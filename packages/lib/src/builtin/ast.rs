@@ -1,10 +1,15 @@
-use proc_macro2::TokenStream;
-use quote::ToTokens;
-use std::borrow::BorrowMut;
+use super::kw;
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use std::borrow::{Borrow, BorrowMut};
 use syn::{
+    bracketed, parenthesized,
     parse::{Nothing as SynNothing, Parse, ParseStream},
+    parse_quote,
     punctuated::Punctuated,
-    token, Data, DeriveInput, Field, Fields, Ident, Path, Result, Token,
+    token, Attribute, Data, DeriveInput, Field, Fields, GenericArgument, GenericParam, Generics,
+    Ident, Lifetime, Lit, Member, Meta, NestedMeta, Path, PathArguments, Result, ReturnType, Token,
+    Type, Variant, WherePredicate,
 };
 
 pub struct Nothing(SynNothing);
@@ -62,40 +67,174 @@ impl Parse for Selectors {
 }
 
 impl Selectors {
-    pub fn select(&self, name: &Ident) -> Option<Ident> {
+    /// Selects an attribute or a field by its name, returns the new name if
+    /// it's selected.
+    pub fn select(&self, name: &Ident) -> Result<Option<Ident>> {
+        match self.0.iter().find(|arg| arg.pattern.matches_name(name)) {
+            Some(arg) => arg.rename(name),
+            None => Ok(None),
+        }
+    }
+
+    /// Selects a field by its name or type, returns the new name if it's
+    /// selected, the name of an unnamed field is always `None`.
+    pub fn select_field(&self, field: &Field) -> Result<Option<Option<Ident>>> {
+        let arg = self.0.iter().find(|arg| arg.pattern.matches_field(field));
+        Ok(match (&field.ident, arg) {
+            (Some(name), Some(arg)) => arg.rename(name)?.map(Some),
+            // Unnamed fields can only be filtered by types or attributes.
+            (None, _) if !self.has_field_pattern() => Some(None),
+            (None, Some(arg)) if !arg.is_dropping() => Some(None),
+            _ => None,
+        })
+    }
+
+    /// Selects a variant by its name or attributes, returns the new name if
+    /// it's selected.
+    pub fn select_variant(&self, variant: &Variant) -> Result<Option<Ident>> {
+        match self
+            .0
+            .iter()
+            .find(|arg| arg.pattern.matches_variant(variant))
+        {
+            Some(arg) => arg.rename(&variant.ident),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns whether any of selectors matches the variant.
+    pub fn matches_variant(&self, variant: &Variant) -> bool {
+        self.0
+            .iter()
+            .any(|arg| arg.pattern.matches_variant(variant))
+    }
+
+    /// Returns whether any of selectors matches the field.
+    pub fn matches_field(&self, field: &Field) -> bool {
+        self.0.iter().any(|arg| arg.pattern.matches_field(field))
+    }
+
+    fn has_field_pattern(&self) -> bool {
+        self.0
+            .iter()
+            .any(|arg| matches!(arg.pattern, Pattern::Type(_) | Pattern::Attr(_)))
+    }
+
+    /// Removes marker attributes matched by patterns with `strip`.
+    pub fn strip_markers(&self, attrs: &mut Vec<Attribute>) {
         for arg in self.0.iter() {
-            match &arg.name {
-                WildName::Wild(_) => return Some(name.clone()),
-                WildName::Name(pat) if name == pat => match &arg.rename {
-                    Some(WildName::Name(rename)) => return Some(rename.clone()),
-                    None => return Some(name.clone()),
-                    _ => return None,
-                },
-                _ => {}
+            if let Pattern::Attr(pat @ AttrPattern { strip: Some(_), .. }) = &arg.pattern {
+                attrs.retain(|attr| !pat.matches(attr));
+            }
+        }
+    }
+
+    /// Ensures every named selector matches at least one of the names.
+    pub fn ensure_exist<'a>(
+        &self,
+        kind: &str,
+        names: impl Clone + Iterator<Item = &'a Ident>,
+    ) -> Result<()> {
+        let mut error = None::<syn::Error>;
+        for arg in self.0.iter() {
+            if let Pattern::Name(WildName::Name(pat)) = &arg.pattern {
+                if names.clone().all(|name| name != pat) {
+                    let e = syn::Error::new(pat.span(), format!("no {kind} named `{pat}`"));
+                    combine_error(&mut error, e);
+                }
             }
         }
-        None
+        error.map_or(Ok(()), Err)
+    }
+
+    /// Ensures no selector renames, which is meaningless for some commands.
+    pub fn ensure_no_rename(&self) -> Result<()> {
+        match self.0.iter().find_map(|arg| arg.as_token) {
+            Some(t) => Err(syn::Error::new(t.span, "renaming is not supported here")),
+            None => Ok(()),
+        }
     }
 }
 
 pub struct Selector {
-    pub name: WildName,
+    pub pattern: Pattern,
     pub as_token: Option<Token![as]>,
     pub rename: Option<WildName>,
 }
 
+impl Selector {
+    /// Returns the new name of a matched item, or `None` if it's dropped.
+    fn rename(&self, name: &Ident) -> Result<Option<Ident>> {
+        if self.is_dropping() {
+            return Ok(None);
+        }
+        Ok(Some(match (&self.pattern, &self.rename) {
+            (
+                Pattern::Name(WildName::Name(_) | WildName::Glob(_)),
+                Some(WildName::Name(rename)),
+            ) => rename.clone(),
+            (Pattern::Name(WildName::Glob(pat)), Some(WildName::Glob(rename))) => {
+                let name_str = name.to_string();
+                let captures = pat.captures(&name_str).unwrap_or_default();
+                let renamed = rename.expand(&captures);
+                syn::parse_str::<Ident>(&renamed)
+                    .map(|mut t| {
+                        t.set_span(name.span());
+                        t
+                    })
+                    .map_err(|_| {
+                        syn::Error::new(
+                            rename.span,
+                            format!("`{name}` is renamed to an invalid identifier `{renamed}`"),
+                        )
+                    })?
+            }
+            _ => name.clone(),
+        }))
+    }
+
+    /// Returns whether matched items are dropped, i.e. renamed to `_`.
+    fn is_dropping(&self) -> bool {
+        !matches!(self.pattern, Pattern::Name(WildName::Wild(_)))
+            && matches!(self.rename, Some(WildName::Wild(_)))
+    }
+}
+
 impl Parse for Selector {
     fn parse(input: ParseStream) -> Result<Self> {
-        let name = input.parse()?;
+        let pattern = input.parse()?;
         if input.peek(Token![as]) {
+            let as_token = input.parse()?;
+            let rename = input.parse()?;
+            match (&pattern, &rename) {
+                (Pattern::Type(_) | Pattern::Attr(_), WildName::Name(_) | WildName::Glob(_)) => {
+                    return Err(syn::Error::new(
+                        rename.span(),
+                        "fields selected by types or attributes can only be renamed to `_`",
+                    ));
+                }
+                (pattern, WildName::Glob(rename)) => {
+                    let stars = match pattern {
+                        Pattern::Name(WildName::Glob(pat)) => pat.stars(),
+                        _ => 0,
+                    };
+                    if rename.stars() > stars {
+                        return Err(syn::Error::new(
+                            rename.span,
+                            "the rename has more `*` than the pattern",
+                        ));
+                    }
+                }
+                _ => {}
+            }
             Ok(Self {
-                name,
-                as_token: Some(input.parse()?),
-                rename: Some(input.parse()?),
+                pattern,
+                as_token: Some(as_token),
+                rename: Some(rename),
             })
         } else {
             Ok(Self {
-                name,
+                pattern,
                 as_token: None,
                 rename: None,
             })
@@ -103,15 +242,100 @@ impl Parse for Selector {
     }
 }
 
+/// Matches an item by its name, e.g. `a` or `_`, or matches a field by its
+/// type, e.g. `: Option<_> | Vec<_>`, or by its attributes, e.g.
+/// `#[serde(skip)]`.
+pub enum Pattern {
+    Name(WildName),
+    Type(TypePattern),
+    Attr(AttrPattern),
+}
+
+impl Pattern {
+    fn matches_name(&self, name: &Ident) -> bool {
+        match self {
+            Pattern::Name(WildName::Wild(_)) => true,
+            Pattern::Name(WildName::Name(pat)) => name == pat,
+            Pattern::Name(WildName::Glob(pat)) => pat.captures(&name.to_string()).is_some(),
+            Pattern::Type(_) | Pattern::Attr(_) => false,
+        }
+    }
+
+    fn matches_variant(&self, variant: &Variant) -> bool {
+        match self {
+            Pattern::Attr(pat) => variant.attrs.iter().any(|attr| pat.matches(attr)),
+            _ => self.matches_name(&variant.ident),
+        }
+    }
+
+    fn matches_field(&self, field: &Field) -> bool {
+        match self {
+            Pattern::Type(pat) => pat.matches(&field.ty),
+            Pattern::Attr(pat) => field.attrs.iter().any(|attr| pat.matches(attr)),
+            Pattern::Name(WildName::Wild(_)) => true,
+            Pattern::Name(_) => matches!(&field.ident, Some(t) if self.matches_name(t)),
+        }
+    }
+}
+
+impl Parse for Pattern {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Token![:]) {
+            input.parse().map(Self::Type)
+        } else if input.peek(Token![#]) {
+            input.parse().map(Self::Attr)
+        } else {
+            input.parse().map(Self::Name)
+        }
+    }
+}
+
+#[allow(dead_code)]
+pub struct TypePattern {
+    pub colon_token: Token![:],
+    pub types: Punctuated<Type, Token![|]>,
+}
+
+impl TypePattern {
+    pub fn matches(&self, ty: &Type) -> bool {
+        self.types.iter().any(|pat| match_type(pat, ty))
+    }
+}
+
+impl Parse for TypePattern {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            colon_token: input.parse()?,
+            types: Punctuated::parse_separated_nonempty(input)?,
+        })
+    }
+}
+
+/// A name, `_`, or a glob pattern like `user_*`.
 pub enum WildName {
     Wild(Token![_]),
     Name(Ident),
+    Glob(Glob),
+}
+
+impl WildName {
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Wild(t) => t.span,
+            Self::Name(t) => t.span(),
+            Self::Glob(t) => t.span,
+        }
+    }
 }
 
 impl Parse for WildName {
     fn parse(input: ParseStream) -> Result<Self> {
         let lookahead = input.lookahead1();
-        if lookahead.peek(Token![_]) {
+        if lookahead.peek(Token![*])
+            || (input.peek(Ident) || input.peek(Token![_])) && input.peek2(Token![*])
+        {
+            input.parse().map(Self::Glob)
+        } else if lookahead.peek(Token![_]) {
             input.parse().map(Self::Wild)
         } else if lookahead.peek(Ident) {
             input.parse().map(Self::Name)
@@ -121,6 +345,456 @@ impl Parse for WildName {
     }
 }
 
+/// A glob pattern in which each `*` matches any part of a name, e.g.
+/// `user_*`, `*_id`. Matched parts can be referred in a rename by `*`s in
+/// order, e.g. `old_* as new_*`.
+pub struct Glob {
+    pub span: Span,
+    parts: Vec<GlobPart>,
+}
+
+enum GlobPart {
+    Text(String),
+    Star,
+}
+
+impl Glob {
+    fn stars(&self) -> usize {
+        self.parts
+            .iter()
+            .filter(|t| matches!(t, GlobPart::Star))
+            .count()
+    }
+
+    /// Returns the matched parts of `*`s if the name matches.
+    fn captures<'a>(&self, name: &'a str) -> Option<Vec<&'a str>> {
+        fn walk<'a>(parts: &[GlobPart], name: &'a str, captures: &mut Vec<&'a str>) -> bool {
+            match parts.split_first() {
+                None => name.is_empty(),
+                Some((GlobPart::Text(text), parts)) => {
+                    matches!(name.strip_prefix(text.as_str()), Some(name) if walk(parts, name, captures))
+                }
+                Some((GlobPart::Star, parts)) => {
+                    for (i, _) in name.char_indices().chain(Some((name.len(), ' '))) {
+                        captures.push(&name[..i]);
+                        if walk(parts, &name[i..], captures) {
+                            return true;
+                        }
+                        captures.pop();
+                    }
+                    false
+                }
+            }
+        }
+        let mut captures = Vec::new();
+        walk(&self.parts, name, &mut captures).then_some(captures)
+    }
+
+    /// Replaces `*`s with the captured parts in order.
+    fn expand(&self, captures: &[&str]) -> String {
+        let mut captures = captures.iter();
+        self.parts
+            .iter()
+            .map(|t| match t {
+                GlobPart::Text(text) => text.as_str(),
+                GlobPart::Star => captures.next().copied().unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
+impl Parse for Glob {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.span();
+        let mut parts = Vec::new();
+        loop {
+            let after_star = matches!(parts.last(), None | Some(GlobPart::Star));
+            if input.peek(Token![*]) {
+                input.parse::<Token![*]>()?;
+                parts.push(GlobPart::Star);
+            } else if after_star && input.peek(Ident) {
+                parts.push(GlobPart::Text(input.parse::<Ident>()?.to_string()));
+            } else if after_star && input.peek(Token![_]) {
+                input.parse::<Token![_]>()?;
+                parts.push(GlobPart::Text("_".to_owned()));
+            } else {
+                break;
+            }
+        }
+        Ok(Self { span, parts })
+    }
+}
+
+/// Returns whether the type matches the pattern, in which `_` matches any type
+/// and `'_` matches any lifetime. Paths match if one is the trailing segments
+/// of the other, e.g. `Option` and `std::option::Option`, and a segment
+/// without arguments matches any arguments.
+pub fn match_type(pat: &Type, ty: &Type) -> bool {
+    match (pat, ty) {
+        (Type::Infer(_), _) => true,
+        (Type::Group(pat), ty) => match_type(&pat.elem, ty),
+        (pat, Type::Group(ty)) => match_type(pat, &ty.elem),
+        (Type::Paren(pat), ty) => match_type(&pat.elem, ty),
+        (pat, Type::Paren(ty)) => match_type(pat, &ty.elem),
+        (Type::Path(pat), Type::Path(ty)) if pat.qself.is_none() && ty.qself.is_none() => {
+            match_path(&pat.path, &ty.path)
+        }
+        (Type::Reference(pat), Type::Reference(ty)) => {
+            pat.mutability.is_some() == ty.mutability.is_some()
+                && match_lifetime(pat.lifetime.as_ref(), ty.lifetime.as_ref())
+                && match_type(&pat.elem, &ty.elem)
+        }
+        (Type::Ptr(pat), Type::Ptr(ty)) => {
+            pat.mutability.is_some() == ty.mutability.is_some() && match_type(&pat.elem, &ty.elem)
+        }
+        (Type::Slice(pat), Type::Slice(ty)) => match_type(&pat.elem, &ty.elem),
+        (Type::Array(pat), Type::Array(ty)) => {
+            let len = pat.len.to_token_stream().to_string();
+            (len == "_" || len == ty.len.to_token_stream().to_string())
+                && match_type(&pat.elem, &ty.elem)
+        }
+        (Type::Tuple(pat), Type::Tuple(ty)) => {
+            pat.elems.len() == ty.elems.len()
+                && pat
+                    .elems
+                    .iter()
+                    .zip(ty.elems.iter())
+                    .all(|(p, t)| match_type(p, t))
+        }
+        (pat, ty) => pat.to_token_stream().to_string() == ty.to_token_stream().to_string(),
+    }
+}
+
+fn match_path(pat: &Path, path: &Path) -> bool {
+    // The shorter one can't be a trailing part if it's an absolute path.
+    let (pat_len, len) = (pat.segments.len(), path.segments.len());
+    if pat.leading_colon.is_some() && pat_len < len || path.leading_colon.is_some() && len < pat_len
+    {
+        return false;
+    }
+    pat.segments
+        .iter()
+        .rev()
+        .zip(path.segments.iter().rev())
+        .all(|(pat, seg)| {
+            pat.ident == seg.ident
+                && match (&pat.arguments, &seg.arguments) {
+                    (PathArguments::None, _) => true,
+                    (PathArguments::AngleBracketed(pat), PathArguments::AngleBracketed(args)) => {
+                        pat.args.len() == args.args.len()
+                            && pat.args.iter().zip(args.args.iter()).all(|(pat, arg)| {
+                                match (pat, arg) {
+                                    (GenericArgument::Type(pat), GenericArgument::Type(ty)) => {
+                                        match_type(pat, ty)
+                                    }
+                                    (
+                                        GenericArgument::Lifetime(pat),
+                                        GenericArgument::Lifetime(lt),
+                                    ) => match_lifetime(Some(pat), Some(lt)),
+                                    (pat, arg) => {
+                                        pat.to_token_stream().to_string()
+                                            == arg.to_token_stream().to_string()
+                                    }
+                                }
+                            })
+                    }
+                    (PathArguments::Parenthesized(pat), PathArguments::Parenthesized(args)) => {
+                        pat.inputs.len() == args.inputs.len()
+                            && pat
+                                .inputs
+                                .iter()
+                                .zip(args.inputs.iter())
+                                .all(|(p, t)| match_type(p, t))
+                            && match (&pat.output, &args.output) {
+                                (ReturnType::Default, ReturnType::Default) => true,
+                                (ReturnType::Type(_, pat), ReturnType::Type(_, ty)) => {
+                                    match_type(pat, ty)
+                                }
+                                _ => false,
+                            }
+                    }
+                    _ => false,
+                }
+        })
+}
+
+fn match_lifetime(pat: Option<&Lifetime>, lt: Option<&Lifetime>) -> bool {
+    match (pat, lt) {
+        (None, _) => true,
+        (Some(pat), _) if pat.ident == "_" => true,
+        (Some(pat), Some(lt)) => pat == lt,
+        (Some(_), None) => false,
+    }
+}
+
+/// Matches a field which has the attribute, e.g. `#[serde(skip)]` matches
+/// `#[serde(default, skip)]`. The attribute is removed from selected fields if
+/// followed by `strip`.
+#[allow(dead_code)]
+pub struct AttrPattern {
+    pub pound_token: Token![#],
+    pub bracket_token: token::Bracket,
+    pub meta: Meta,
+    pub strip: Option<kw::strip>,
+}
+
+impl AttrPattern {
+    pub fn matches(&self, attr: &Attribute) -> bool {
+        matches!(attr.parse_meta(), Ok(meta) if match_meta(&self.meta, &meta))
+    }
+}
+
+impl Parse for AttrPattern {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        Ok(Self {
+            pound_token: input.parse()?,
+            bracket_token: bracketed!(content in input),
+            meta: content.parse()?,
+            strip: input.parse()?,
+        })
+    }
+}
+
+fn match_meta(pat: &Meta, meta: &Meta) -> bool {
+    let eq = |a: &dyn ToTokens, b: &dyn ToTokens| {
+        a.to_token_stream().to_string() == b.to_token_stream().to_string()
+    };
+    if !eq(pat.path(), meta.path()) {
+        return false;
+    }
+    match (pat, meta) {
+        (Meta::Path(_), _) => true,
+        (Meta::List(pat), Meta::List(list)) => pat.nested.iter().all(|pat| {
+            list.nested.iter().any(|nested| match (pat, nested) {
+                (NestedMeta::Meta(pat), NestedMeta::Meta(meta)) => match_meta(pat, meta),
+                (pat, nested) => eq(pat, nested),
+            })
+        }),
+        (Meta::NameValue(pat), Meta::NameValue(meta)) => eq(&pat.lit, &meta.lit),
+        _ => false,
+    }
+}
+
+/// Where a modification is applied to, e.g. `fields:`, `field <name>:`,
+/// `variants:` or `variant <name>:`, defaults to the type.
+#[allow(dead_code)]
+pub enum Target {
+    Type,
+    Fields(kw::fields, Token![:]),
+    Field(kw::field, Member, Token![:]),
+    Variants(kw::variants, Token![:]),
+    Variant(kw::variant, Ident, Token![:]),
+}
+
+impl Target {
+    /// Calls `f` on targeted fields, fails if the specified field is not found.
+    pub fn for_each_field(
+        &self,
+        data: &mut DeriveInput,
+        mut f: impl FnMut(&mut Field),
+    ) -> Result<()> {
+        let mut found = false;
+        for fields in data.fields_iter() {
+            for (i, field) in fields.iter_mut().enumerate() {
+                let matched = match (self, &field.ident) {
+                    (Target::Fields(..), _) => true,
+                    (Target::Field(_, Member::Named(name), _), Some(ident)) => name == ident,
+                    (Target::Field(_, Member::Unnamed(index), _), None) => {
+                        index.index as usize == i
+                    }
+                    _ => false,
+                };
+                if matched {
+                    f(field);
+                    found = true;
+                }
+            }
+        }
+        match self {
+            Target::Field(_, member, _) if !found => Err(syn::Error::new_spanned(
+                member,
+                format!("no field named `{}`", member.to_token_stream()),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Calls `f` on targeted variants, fails if the data is not an enum or the
+    /// specified variant is not found.
+    pub fn for_each_variant(
+        &self,
+        data: &mut DeriveInput,
+        span: Span,
+        mut f: impl FnMut(&mut Variant),
+    ) -> Result<()> {
+        let variants = data
+            .variants_mut()
+            .ok_or_else(|| syn::Error::new(span, "only enum has variants"))?;
+        let mut found = false;
+        for variant in variants.iter_mut() {
+            match self {
+                Target::Variant(_, name, _) if &variant.ident != name => continue,
+                _ => {}
+            }
+            f(variant);
+            found = true;
+        }
+        match self {
+            Target::Variant(_, name, _) if !found => Err(syn::Error::new(
+                name.span(),
+                format!("no variant named `{name}`"),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Parse for Target {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(kw::fields) && input.peek2(Token![:]) {
+            Ok(Self::Fields(input.parse()?, input.parse()?))
+        } else if input.peek(kw::field) && !input.peek2(Token![:]) {
+            Ok(Self::Field(input.parse()?, input.parse()?, input.parse()?))
+        } else if input.peek(kw::variants) && input.peek2(Token![:]) {
+            Ok(Self::Variants(input.parse()?, input.parse()?))
+        } else if input.peek(kw::variant) && !input.peek2(Token![:]) {
+            Ok(Self::Variant(
+                input.parse()?,
+                input.parse()?,
+                input.parse()?,
+            ))
+        } else {
+            Ok(Self::Type)
+        }
+    }
+}
+
+/// Returns whether the attribute is a marker of this crate, e.g.
+/// `#[transtype(nowrap)]`, which is removed before emitting the type.
+pub fn is_marker(attr: &Attribute) -> bool {
+    attr.path.is_ident("transtype")
+}
+
+/// Removes the marker from attributes, e.g. `nowrap` from
+/// `#[transtype(nowrap)]`, returns whether it's found.
+pub fn take_marker(attrs: &mut Vec<Attribute>, name: &str) -> Result<bool> {
+    let mut found = false;
+    for mut attr in std::mem::take(attrs) {
+        if is_marker(&attr) {
+            let items: Punctuated<NestedMeta, Token![,]> =
+                attr.parse_args_with(Punctuated::parse_terminated)?;
+            let len = items.len();
+            let items = items
+                .into_iter()
+                .filter(|t| !matches!(t, NestedMeta::Meta(Meta::Path(path)) if path.is_ident(name)))
+                .collect::<Punctuated<_, Token![,]>>();
+            if items.len() != len {
+                found = true;
+                if items.is_empty() {
+                    continue;
+                }
+                attr.tokens = quote!((#items));
+            }
+        }
+        attrs.push(attr);
+    }
+    Ok(found)
+}
+
+/// Adds an error to the collected ones.
+pub fn combine_error(error: &mut Option<syn::Error>, e: syn::Error) {
+    match error {
+        Some(error) => error.combine(e),
+        None => *error = Some(e),
+    }
+}
+
+/// Returns the member of a field, tuple fields are named by index.
+pub fn field_member(i: usize, field: &Field) -> Member {
+    field
+        .ident
+        .clone()
+        .map(Member::Named)
+        .unwrap_or_else(|| Member::Unnamed(i.into()))
+}
+
+/// Returns the member of the origin type which the field comes from, which is
+/// recorded by `#[transtype(origin(...))]`.
+pub fn origin_member(field: &Field) -> Option<Member> {
+    field.attrs.iter().find_map(origin_marker)
+}
+
+/// Returns the name of the variant of the origin type which the variant comes
+/// from, which is recorded by `#[transtype(origin(...))]`.
+pub fn origin_variant(variant: &Variant) -> Option<Ident> {
+    match variant.attrs.iter().find_map(origin_marker)? {
+        Member::Named(ident) => Some(ident),
+        Member::Unnamed(_) => None,
+    }
+}
+
+fn origin_marker(attr: &Attribute) -> Option<Member> {
+    if !is_marker(attr) {
+        return None;
+    }
+    let items: Punctuated<NestedMeta, Token![,]> =
+        attr.parse_args_with(Punctuated::parse_terminated).ok()?;
+    items.into_iter().find_map(|item| match item {
+        NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("origin") => {
+            match list.nested.first()? {
+                NestedMeta::Meta(Meta::Path(path)) => path.get_ident().cloned().map(Member::Named),
+                NestedMeta::Lit(Lit::Int(index)) => index
+                    .base10_parse::<usize>()
+                    .ok()
+                    .map(|t| Member::Unnamed(t.into())),
+                _ => None,
+            }
+        }
+        _ => None,
+    })
+}
+
+/// Returns how many wrappers `wrap` has added to the field, each one is
+/// recorded by `#[transtype(wrapped)]`.
+pub fn wrapped_layers(field: &Field) -> usize {
+    field.attrs.iter().filter(|t| is_wrapped_marker(t)).count()
+}
+
+/// Removes the marker of the outermost wrapper.
+pub fn pop_wrapped(field: &mut Field) {
+    if let Some(i) = field.attrs.iter().rposition(is_wrapped_marker) {
+        field.attrs.remove(i);
+    }
+}
+
+fn is_wrapped_marker(attr: &Attribute) -> bool {
+    is_marker(attr) && matches!(attr.parse_args::<Ident>(), Ok(t) if t == "wrapped")
+}
+
+/// Returns the marker which records that `From<Self>` is implemented for the
+/// type, e.g. `#[transtype(into(A))]`.
+pub fn into_marker(ty: &Type) -> Attribute {
+    parse_quote!(#[transtype(into(#ty))])
+}
+
+fn is_into_marker(attr: &Attribute) -> bool {
+    is_marker(attr)
+        && attr
+            .parse_args_with(|input: ParseStream| {
+                input.parse::<kw::into>()?;
+                let content;
+                parenthesized!(content in input);
+                content.parse::<Type>()
+            })
+            .is_ok()
+}
+
+/// Parses traits in a `#[derive(...)]` attribute.
+pub fn parse_derive(attr: &Attribute) -> Result<Punctuated<Path, Token![,]>> {
+    attr.parse_args_with(Punctuated::parse_terminated)
+}
+
 trait BorrowMut2 {
     fn borrow_mut2<T>(&mut self) -> &mut T
     where
@@ -147,6 +821,70 @@ pub trait DeriveInputExt: BorrowMut<DeriveInput> {
             Data::Union(data) => Box::new(std::iter::once(&mut data.fields.named)),
         }
     }
+
+    fn variants_mut(&mut self) -> Option<&mut Punctuated<Variant, Token![,]>> {
+        match &mut self.borrow_mut2::<DeriveInput>().data {
+            Data::Enum(data) => Some(&mut data.variants),
+            _ => None,
+        }
+    }
+
+    /// Records where every variant and field comes from, the origin is kept
+    /// through commands which rename, reorder or remove them.
+    fn mark_origin(&mut self) {
+        if let Some(variants) = self.variants_mut() {
+            for variant in variants.iter_mut() {
+                let name = &variant.ident;
+                variant
+                    .attrs
+                    .push(parse_quote!(#[transtype(origin(#name))]));
+            }
+        }
+        for fields in self.fields_iter() {
+            for (i, field) in fields.iter_mut().enumerate() {
+                let member = field_member(i, field);
+                field
+                    .attrs
+                    .push(parse_quote!(#[transtype(origin(#member))]));
+            }
+        }
+    }
+
+    /// Removes markers recorded by `mark_origin`, `wrap` and `wrapped`.
+    fn remove_tracking(&mut self) {
+        self.borrow_mut2::<DeriveInput>()
+            .attrs
+            .retain(|t| !is_into_marker(t));
+        if let Some(variants) = self.variants_mut() {
+            for variant in variants.iter_mut() {
+                variant.attrs.retain(|t| origin_marker(t).is_none());
+            }
+        }
+        for fields in self.fields_iter() {
+            for field in fields.iter_mut() {
+                field
+                    .attrs
+                    .retain(|t| origin_marker(t).is_none() && !is_wrapped_marker(t));
+            }
+        }
+    }
+
+    /// Removes all markers of this crate from the type, its variants and
+    /// fields.
+    fn remove_markers(&mut self) {
+        let data = self.borrow_mut2::<DeriveInput>();
+        data.attrs.retain(|t| !is_marker(t));
+        if let Some(variants) = data.variants_mut() {
+            for variant in variants.iter_mut() {
+                variant.attrs.retain(|t| !is_marker(t));
+            }
+        }
+        for fields in data.fields_iter() {
+            for field in fields.iter_mut() {
+                field.attrs.retain(|t| !is_marker(t));
+            }
+        }
+    }
 }
 
 impl<T: BorrowMut<DeriveInput>> DeriveInputExt for T {}
@@ -163,6 +901,60 @@ pub trait FieldsExt: BorrowMut<Fields> {
 
 impl<T: BorrowMut<Fields>> FieldsExt for T {}
 
+pub trait GenericsExt: BorrowMut<Generics> {
+    /// Merges parameters and where predicates of `other` into this one,
+    /// parameters already declared are skipped, but their bounds are kept in
+    /// the where clause.
+    fn merge(&mut self, other: Generics) {
+        let this = self.borrow_mut2::<Generics>();
+        let mut params = std::mem::take(&mut this.params)
+            .into_iter()
+            .collect::<Vec<_>>();
+        let mut predicates = Vec::<WherePredicate>::new();
+        for param in other.params {
+            let name = generic_param_name(&param);
+            if params.iter().all(|t| generic_param_name(t) != name) {
+                params.push(param);
+                continue;
+            }
+            match param {
+                GenericParam::Type(t) if !t.bounds.is_empty() => {
+                    let (ident, bounds) = (t.ident, t.bounds);
+                    predicates.push(parse_quote!(#ident: #bounds));
+                }
+                GenericParam::Lifetime(t) if !t.bounds.is_empty() => {
+                    let (lifetime, bounds) = (t.lifetime, t.bounds);
+                    predicates.push(parse_quote!(#lifetime: #bounds));
+                }
+                _ => {}
+            }
+        }
+        // Lifetimes must be declared prior to other parameters.
+        params.sort_by_key(|t| !matches!(t, GenericParam::Lifetime(_)));
+        this.params = params.into_iter().collect();
+        if !this.params.is_empty() {
+            this.lt_token.get_or_insert_with(Default::default);
+            this.gt_token.get_or_insert_with(Default::default);
+        }
+        if let Some(other) = other.where_clause {
+            predicates.extend(other.predicates);
+        }
+        if !predicates.is_empty() {
+            this.make_where_clause().predicates.extend(predicates);
+        }
+    }
+}
+
+impl<T: BorrowMut<Generics>> GenericsExt for T {}
+
+fn generic_param_name(param: &GenericParam) -> String {
+    match param {
+        GenericParam::Lifetime(t) => t.lifetime.to_string(),
+        GenericParam::Type(t) => t.ident.to_string(),
+        GenericParam::Const(t) => t.ident.to_string(),
+    }
+}
+
 pub trait PathExt: BorrowMut<Path> {
     fn get_ident_mut(&mut self) -> Option<&mut Ident> {
         let path = self.borrow_mut2::<Path>();
@@ -179,10 +971,23 @@ pub trait PathExt: BorrowMut<Path> {
 
 impl<T: BorrowMut<Path>> PathExt for T {}
 
-pub trait TokenStreamExt: Into<TokenStream> {
-    fn parse2<T: Parse>(self) -> Result<T> {
-        syn::parse2(self.into())
+pub trait TypeExt: Borrow<Type> {
+    /// Converts a type path into its expression form, e.g. `A<T>` into
+    /// `A::<T>`, which is suitable to construct a struct.
+    fn to_expr_path(&self) -> Result<Path> {
+        match Borrow::<Type>::borrow(self) {
+            Type::Path(ty) if ty.qself.is_none() => {
+                let mut path = ty.path.clone();
+                for segment in path.segments.iter_mut() {
+                    if let PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                        args.colon2_token.get_or_insert_with(Default::default);
+                    }
+                }
+                Ok(path)
+            }
+            ty => Err(syn::Error::new_spanned(ty, "expected a path to a type")),
+        }
     }
 }
 
-impl<T: Into<TokenStream>> TokenStreamExt for T {}
+impl<T: Borrow<Type>> TypeExt for T {}
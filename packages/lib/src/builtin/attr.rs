@@ -0,0 +1,191 @@
+use super::{
+    ast::{combine_error, parse_derive, DeriveInputExt, Target},
+    kw,
+};
+use crate::{TransformRest, TransformState, Transformer};
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Attribute, DeriveInput, Path, Result, Token,
+};
+
+pub(crate) struct Derive;
+
+impl Transformer for Derive {
+    type Args = DeriveArgs;
+
+    fn transform(
+        mut data: DeriveInput,
+        args: Self::Args,
+        rest: &mut TransformRest,
+    ) -> Result<TransformState> {
+        let mut removed = vec![false; args.edits.len()];
+        if args.variants.is_some() {
+            let variants = data
+                .variants_mut()
+                .ok_or_else(|| syn::Error::new(rest.span(), "only enum has variants"))?;
+            for variant in variants.iter_mut() {
+                args.apply(&mut variant.attrs, &mut removed)?;
+            }
+        } else {
+            args.apply(&mut data.attrs, &mut removed)?;
+        }
+        let mut error = None::<syn::Error>;
+        for (edit, removed) in args.edits.iter().zip(removed) {
+            if let DeriveEdit::Remove(_, path) = edit {
+                if !removed {
+                    let e = syn::Error::new_spanned(
+                        path,
+                        format!("`{}` is not derived", path.to_token_stream()),
+                    );
+                    combine_error(&mut error, e);
+                }
+            }
+        }
+        if let Some(e) = error {
+            return Err(e);
+        }
+        Ok(TransformState::pipe(data).build())
+    }
+}
+
+/// Arguments of `derive`, e.g. `+Default, -Copy` or `variants: +Default`.
+pub struct DeriveArgs {
+    pub variants: Option<(kw::variants, Token![:])>,
+    pub edits: Punctuated<DeriveEdit, Token![,]>,
+}
+
+impl DeriveArgs {
+    /// Merges all `#[derive]`s into one and applies edits on it, the merged
+    /// attribute is placed at where the first one was.
+    fn apply(&self, attrs: &mut Vec<Attribute>, removed: &mut [bool]) -> Result<()> {
+        let mut derives = Punctuated::<Path, Token![,]>::new();
+        let mut position = None;
+        let mut template = None;
+        let mut i = 0;
+        while i < attrs.len() {
+            if attrs[i].path.is_ident("derive") {
+                let attr = attrs.remove(i);
+                derives.extend(parse_derive(&attr)?);
+                position.get_or_insert(i);
+                template.get_or_insert(attr);
+            } else {
+                i += 1;
+            }
+        }
+        for (edit, removed) in self.edits.iter().zip(removed.iter_mut()) {
+            match edit {
+                DeriveEdit::Add(_, path) => {
+                    if !derives.iter().any(|t| same_trait(path, t)) {
+                        derives.push(path.clone());
+                    }
+                }
+                DeriveEdit::Remove(_, path) => {
+                    let len = derives.len();
+                    derives = std::mem::take(&mut derives)
+                        .into_iter()
+                        .filter(|t| !same_trait(path, t))
+                        .collect();
+                    *removed |= derives.len() != len;
+                }
+            }
+        }
+        if !derives.is_empty() {
+            let mut attr = template.unwrap_or_else(|| syn::parse_quote!(#[derive()]));
+            attr.tokens = quote!((#derives));
+            attrs.insert(position.unwrap_or(0), attr);
+        }
+        Ok(())
+    }
+}
+
+impl Parse for DeriveArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            variants: if input.peek(kw::variants) && input.peek2(Token![:]) {
+                Some((input.parse()?, input.parse()?))
+            } else {
+                None
+            },
+            edits: Punctuated::parse_terminated(input)?,
+        })
+    }
+}
+
+/// Adds a trait by `+Trait` or `Trait`, removes a trait by `-Trait`.
+#[allow(dead_code)]
+pub enum DeriveEdit {
+    Add(Option<Token![+]>, Path),
+    Remove(Token![-], Path),
+}
+
+impl Parse for DeriveEdit {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Token![-]) {
+            Ok(Self::Remove(input.parse()?, input.parse()?))
+        } else {
+            Ok(Self::Add(input.parse()?, input.parse()?))
+        }
+    }
+}
+
+/// Returns whether both paths refer to the same trait, a single identifier
+/// matches the last segment of a path, e.g. `Copy` matches `core::marker::Copy`.
+fn same_trait(pat: &Path, path: &Path) -> bool {
+    match pat.get_ident() {
+        Some(ident) => path.segments.last().map(|t| &t.ident) == Some(ident),
+        None => pat.to_token_stream().to_string() == path.to_token_stream().to_string(),
+    }
+}
+
+pub(crate) struct Attr;
+
+impl Transformer for Attr {
+    type Args = AttrArgs;
+
+    fn transform(
+        mut data: DeriveInput,
+        args: Self::Args,
+        rest: &mut TransformRest,
+    ) -> Result<TransformState> {
+        for AttrItem { target, attrs } in args.0 {
+            match target {
+                Target::Type => data.attrs.extend(attrs),
+                Target::Fields(..) | Target::Field(..) => target
+                    .for_each_field(&mut data, |field| field.attrs.extend(attrs.iter().cloned()))?,
+                Target::Variants(..) | Target::Variant(..) => {
+                    target.for_each_variant(&mut data, rest.span(), |variant| {
+                        variant.attrs.extend(attrs.iter().cloned())
+                    })?
+                }
+            }
+        }
+        Ok(TransformState::pipe(data).build())
+    }
+}
+
+/// Arguments of `attr`, e.g. `#[a], fields: #[b], field x: #[c] #[d]`.
+pub struct AttrArgs(pub Punctuated<AttrItem, Token![,]>);
+
+impl Parse for AttrArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Punctuated::parse_terminated(input).map(Self)
+    }
+}
+
+pub struct AttrItem {
+    pub target: Target,
+    pub attrs: Vec<Attribute>,
+}
+
+impl Parse for AttrItem {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let target = input.parse()?;
+        let attrs = Attribute::parse_outer(input)?;
+        if attrs.is_empty() {
+            return Err(input.error("expected attributes"));
+        }
+        Ok(Self { target, attrs })
+    }
+}
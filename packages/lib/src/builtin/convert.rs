@@ -0,0 +1,372 @@
+use super::{
+    ast::{combine_error, field_member, origin_member, Delimiter, GenericsExt, TypeExt},
+    kw,
+    wrap::{original_of, WrappedArgs},
+};
+use crate::{TransformRest, TransformState, Transformer};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::{
+    bracketed,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Data, DeriveInput, Expr, Fields, Member, PathArguments, Result, Token, Type,
+};
+
+pub(crate) struct ConvertFrom;
+
+impl Transformer for ConvertFrom {
+    type Args = WrappedArgs;
+
+    fn transform(
+        data: DeriveInput,
+        args: Self::Args,
+        rest: &mut TransformRest,
+    ) -> Result<TransformState> {
+        let span = rest.span();
+        let (original, from, by_origin) = original_of("from", &data, args, rest)?;
+        // `from(&A)` clones fields of the borrowed value.
+        let by_ref = matches!(from, Type::Reference(_));
+        let (original_fields, fields) = match (&original.data, &data.data) {
+            (Data::Struct(original), Data::Struct(data)) => (&original.fields, &data.fields),
+            _ => {
+                return Err(syn::Error::new(
+                    span,
+                    format!(
+                        "only struct can be converted from `{}`",
+                        from.to_token_stream()
+                    ),
+                ))
+            }
+        };
+        let members = project_fields(original_fields, fields, &from, by_origin)?;
+        let mut generics = data.generics.clone();
+        generics.merge(original.generics);
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        let (_, ty_generics, _) = data.generics.split_for_impl();
+        let name = &data.ident;
+        let body = construct(fields, &members, span, |member| {
+            if by_ref {
+                quote_spanned!(span=> ::core::clone::Clone::clone(&value.#member))
+            } else {
+                quote_spanned!(span=> value.#member)
+            }
+        });
+        rest.with_extra(quote_spanned!(span=>
+            impl #impl_generics ::core::convert::From<#from> for #name #ty_generics #where_clause {
+                fn from(value: #from) -> Self {
+                    Self #body
+                }
+            }
+        ));
+        Ok(TransformState::pipe(data).build())
+    }
+}
+
+/// Finds the member of the original type for every field, by its origin if
+/// `by_origin` is set, otherwise by name, or by index for tuple fields.
+fn project_fields(
+    original: &Fields,
+    fields: &Fields,
+    from: &Type,
+    by_origin: bool,
+) -> Result<Vec<Member>> {
+    // Fields which aren't tracked never come from the origin.
+    let key = |i, field| {
+        if by_origin {
+            origin_member(field)
+        } else {
+            Some(field_member(i, field))
+        }
+    };
+    let mut error = None::<syn::Error>;
+    let mut members = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        let key = key(i, field);
+        let found = original
+            .iter()
+            .enumerate()
+            .find(|(k, t)| Some(field_member(*k, t)) == key)
+            .map(|(k, t)| field_member(k, t));
+        match found {
+            Some(member) => members.push(member),
+            None => {
+                let e = match &field.ident {
+                    Some(name) => syn::Error::new(
+                        name.span(),
+                        format!(
+                            "field `{}` cannot be mapped to any field of `{}`",
+                            name,
+                            from.to_token_stream(),
+                        ),
+                    ),
+                    None => syn::Error::new_spanned(
+                        &field.ty,
+                        format!(
+                            "field `{}` cannot be mapped to any field of `{}`",
+                            i,
+                            from.to_token_stream(),
+                        ),
+                    ),
+                };
+                combine_error(&mut error, e);
+            }
+        }
+    }
+    error.map_or(Ok(members), Err)
+}
+
+/// Generates the body of the constructor, every field is taken from `value`.
+fn construct(
+    fields: &Fields,
+    members: &[Member],
+    span: Span,
+    value: impl Fn(&Member) -> TokenStream,
+) -> TokenStream {
+    let mut tokens = TokenStream::default();
+    Delimiter::from_feilds(fields).surround(&mut tokens, |tokens| {
+        for (field, member) in fields.iter().zip(members) {
+            let ident = &field.ident;
+            let colon = ident.as_ref().map(|_| quote_spanned!(span=> :));
+            let value = value(member);
+            tokens.extend(quote_spanned!(span=> #ident #colon #value,));
+        }
+    });
+    tokens
+}
+
+pub(crate) struct Convert;
+
+impl Transformer for Convert {
+    type Args = ConvertArgs;
+
+    fn transform(
+        data: DeriveInput,
+        args: Self::Args,
+        rest: &mut TransformRest,
+    ) -> Result<TransformState> {
+        let span = rest.span();
+        let ConvertArgs {
+            source,
+            into,
+            options,
+        } = args;
+        let source = match source {
+            Some((_, source, _)) => source,
+            None => {
+                // Resumes the target type to find out its fields, the
+                // conversion is generated there.
+                let mut path = into.to_expr_path()?;
+                path.segments
+                    .iter_mut()
+                    .for_each(|t| t.arguments = PathArguments::None);
+                rest.with_pipe(syn::parse2(quote_spanned!(span=>
+                    -> convert(as #data, into = #into #options)
+                ))?);
+                return Ok(TransformState::resume(path).build());
+            }
+        };
+        let (source_fields, fields) = match (&source.data, &data.data) {
+            (Data::Struct(source), Data::Struct(data)) => (&source.fields, &data.fields),
+            _ => {
+                return Err(syn::Error::new(
+                    span,
+                    format!(
+                        "only struct can be converted into `{}`",
+                        into.to_token_stream()
+                    ),
+                ))
+            }
+        };
+        let body = convert_fields(source_fields, fields, &options, &source, &into, span)?;
+        let name = &source.ident;
+        let (impl_generics, ty_generics, where_clause) = source.generics.split_for_impl();
+        rest.with_extra(quote_spanned!(span=>
+            impl #impl_generics ::core::convert::From<#name #ty_generics> for #into #where_clause {
+                fn from(value: #name #ty_generics) -> Self {
+                    Self #body
+                }
+            }
+        ));
+        Ok(TransformState::pipe(source).build())
+    }
+}
+
+/// Generates fields of the target type, every field is taken from the field
+/// of the same name, converted by the override or `Into::into`, or defaulted.
+fn convert_fields(
+    source: &Fields,
+    fields: &Fields,
+    options: &ConvertOptions,
+    source_data: &DeriveInput,
+    into: &Type,
+    span: Span,
+) -> Result<TokenStream> {
+    let mut error = None::<syn::Error>;
+    let mut push_error = |e| combine_error(&mut error, e);
+    let members = fields.iter().enumerate().map(|(i, t)| field_member(i, t));
+    let members = members.collect::<Vec<_>>();
+    for member in options
+        .overrides
+        .iter()
+        .map(|(member, _)| member)
+        .chain(options.defaults.iter().flatten())
+    {
+        if !members.contains(member) {
+            push_error(syn::Error::new_spanned(
+                member,
+                format!(
+                    "no field named `{}` in `{}`",
+                    member.to_token_stream(),
+                    into.to_token_stream()
+                ),
+            ));
+        }
+    }
+    let mut unmatched = Vec::new();
+    let mut tokens = TokenStream::default();
+    Delimiter::from_feilds(fields).surround(&mut tokens, |tokens| {
+        for (field, member) in fields.iter().zip(&members) {
+            let found = source
+                .iter()
+                .enumerate()
+                .find(|(i, t)| field_member(*i, t) == *member)
+                .map(|(_, t)| t);
+            let defaulted = match &options.defaults {
+                Some(defaults) => defaults.iter().any(|t| t == member),
+                None => options.default_all,
+            };
+            let value = match (options.find(member), found) {
+                (Some(f), Some(_)) => quote_spanned!(span=>
+                    ::transtype::private::apply(value.#member, #f)
+                ),
+                (Some(_), None) => {
+                    push_error(syn::Error::new(
+                        span,
+                        format!(
+                            "field `{}` doesn't exist in `{}`",
+                            member.to_token_stream(),
+                            source_data.ident
+                        ),
+                    ));
+                    continue;
+                }
+                (None, Some(found)) => {
+                    if found.ty.to_token_stream().to_string()
+                        == field.ty.to_token_stream().to_string()
+                    {
+                        quote_spanned!(span=> value.#member)
+                    } else {
+                        quote_spanned!(span=> ::core::convert::Into::into(value.#member))
+                    }
+                }
+                (None, None) if defaulted => {
+                    quote_spanned!(span=> ::core::default::Default::default())
+                }
+                (None, None) => {
+                    unmatched.push(format!("`{}`", member.to_token_stream()));
+                    continue;
+                }
+            };
+            let ident = &field.ident;
+            let colon = ident.as_ref().map(|_| quote_spanned!(span=> :));
+            tokens.extend(quote_spanned!(span=> #ident #colon #value,));
+        }
+    });
+    if !unmatched.is_empty() {
+        push_error(syn::Error::new(
+            span,
+            format!(
+                "cannot convert `{}` into `{}`, unmatched fields: {}; add an override or list them in `default = [...]`",
+                source_data.ident,
+                into.to_token_stream(),
+                unmatched.join(", "),
+            ),
+        ));
+    }
+    error.map_or(Ok(tokens), Err)
+}
+
+/// Arguments of `convert`, e.g. `into = B, b = |x| x.len(), default = [c]`.
+pub struct ConvertArgs {
+    /// The source data, which is given when the target type is resumed.
+    pub source: Option<(Token![as], DeriveInput, Token![,])>,
+    pub into: Type,
+    pub options: ConvertOptions,
+}
+
+impl Parse for ConvertArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let source = if input.peek(Token![as]) {
+            Some((input.parse()?, input.parse()?, input.parse()?))
+        } else {
+            None
+        };
+        input.parse::<kw::into>()?;
+        input.parse::<Token![=]>()?;
+        Ok(Self {
+            source,
+            into: input.parse()?,
+            options: input.parse()?,
+        })
+    }
+}
+
+/// Per-field overrides and defaults, each one is preceded by a comma.
+#[derive(Default)]
+pub struct ConvertOptions {
+    pub overrides: Vec<(Member, Expr)>,
+    /// Fields to be defaulted, all missing fields if `default_all` is set.
+    pub defaults: Option<Punctuated<Member, Token![,]>>,
+    pub default_all: bool,
+}
+
+impl ConvertOptions {
+    fn find(&self, member: &Member) -> Option<&Expr> {
+        self.overrides
+            .iter()
+            .find(|(t, _)| t == member)
+            .map(|(_, f)| f)
+    }
+}
+
+impl Parse for ConvertOptions {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut options = Self::default();
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            if input.peek(kw::default) {
+                input.parse::<kw::default>()?;
+                if input.peek(Token![=]) {
+                    input.parse::<Token![=]>()?;
+                    let content;
+                    bracketed!(content in input);
+                    options.defaults = Some(Punctuated::parse_terminated(&content)?);
+                } else {
+                    options.default_all = true;
+                }
+                continue;
+            }
+            let member = input.parse::<Member>()?;
+            input.parse::<Token![=]>()?;
+            options.overrides.push((member, input.parse()?));
+        }
+        Ok(options)
+    }
+}
+
+impl ToTokens for ConvertOptions {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        for (member, f) in &self.overrides {
+            tokens.extend(quote!(, #member = #f));
+        }
+        if let Some(defaults) = &self.defaults {
+            tokens.extend(quote!(, default = [#defaults]));
+        } else if self.default_all {
+            tokens.extend(quote!(, default));
+        }
+    }
+}
@@ -1,7 +1,14 @@
+use super::{
+    ast::{combine_error, parse_derive, GenericsExt},
+    kw,
+};
 use crate::{TransformRest, TransformState, Transformer};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned, ToTokens};
 use syn::{
     parse::{Parse, ParseStream},
-    parse_quote_spanned, token, Data, DeriveInput, Fields, Path, Result, Token,
+    punctuated::Punctuated,
+    token, Attribute, Data, DeriveInput, Field, Fields, Ident, Path, Result, Token, Variant,
 };
 
 pub(crate) struct Extend;
@@ -10,58 +17,218 @@ impl Transformer for Extend {
     type Args = ExtendArgs;
 
     fn transform(
-        data: DeriveInput,
+        mut data: DeriveInput,
         args: Self::Args,
         rest: &mut TransformRest,
     ) -> Result<TransformState> {
         let span = rest.span();
         Ok(match args {
-            ExtendArgs::Path(path) => {
-                rest.with_pipe(parse_quote_spanned!(span=>
-                    -> extend(as #data)
-                ));
-                TransformState::resume(path).build()
+            ExtendArgs::As(ExtendAs {
+                data: mut dest,
+                options,
+                ..
+            }) => {
+                extend_data(&mut dest, data, &options, span)?;
+                TransformState::pipe(dest).build()
             }
-            ExtendArgs::As(ExtendAs { data: mut dest, .. }) => {
-                match (&mut dest.data, data.data) {
-                    (Data::Struct(dest), Data::Struct(src)) => {
-                        match (&mut dest.fields, src.fields) {
-                            (Fields::Named(dest), Fields::Named(src)) => {
-                                dest.named.extend(src.named);
+            ExtendArgs::Sources(ExtendSources { sources, options }) => {
+                let mut sources = sources.into_iter();
+                while let Some(source) = sources.next() {
+                    match source {
+                        ExtendSource::Path(path) => {
+                            // Resumes the saved type, and extends current data
+                            // there, the remaining sources are handled later.
+                            let sources = sources.collect::<Punctuated<_, Token![,]>>();
+                            let mut pipe = quote_spanned!(span=>
+                                -> extend(as #data, #options)
+                            );
+                            if !sources.is_empty() {
+                                pipe.extend(quote_spanned!(span=>
+                                    -> extend(#sources, #options)
+                                ));
                             }
-                            _ => todo!(),
+                            rest.with_pipe(syn::parse2(pipe)?);
+                            return Ok(TransformState::resume(path).build());
                         }
+                        ExtendSource::Struct(ExtendStruct { fields, .. }) => match &mut data.data {
+                            Data::Struct(dest) => {
+                                extend_fields(&mut dest.fields, fields, options.on_conflict)?
+                            }
+                            _ => {
+                                return Err(syn::Error::new(
+                                    span,
+                                    "only struct can be extended with fields",
+                                ))
+                            }
+                        },
                     }
-                    _ => todo!(),
                 }
-                TransformState::pipe(dest).build()
+                TransformState::pipe(data).build()
             }
-            ExtendArgs::Struct(_) => todo!(),
         })
     }
 }
 
+fn extend_data(
+    dest: &mut DeriveInput,
+    src: DeriveInput,
+    options: &ExtendOptions,
+    span: Span,
+) -> Result<()> {
+    dest.generics.merge(src.generics);
+    if options.merge_attrs {
+        merge_attrs(&mut dest.attrs, src.attrs)?;
+    }
+    match (&mut dest.data, src.data) {
+        (Data::Struct(dest), Data::Struct(src)) => {
+            extend_fields(&mut dest.fields, src.fields, options.on_conflict)?
+        }
+        (Data::Enum(dest), Data::Enum(src)) => extend_unique(
+            &mut dest.variants,
+            src.variants,
+            options.on_conflict,
+            |t: &Variant| Some(&t.ident),
+            "variant",
+        )?,
+        (Data::Union(dest), Data::Union(src)) => extend_unique(
+            &mut dest.fields.named,
+            src.fields.named,
+            options.on_conflict,
+            |t: &Field| t.ident.as_ref(),
+            "field",
+        )?,
+        (dest, src) => {
+            return Err(syn::Error::new(
+                span,
+                format!("cannot extend {} with {}", data_kind(dest), data_kind(&src)),
+            ))
+        }
+    }
+    Ok(())
+}
+
+fn extend_fields(dest: &mut Fields, src: Fields, on_conflict: Conflict) -> Result<()> {
+    match (dest, src) {
+        (Fields::Named(dest), Fields::Named(src)) => extend_unique(
+            &mut dest.named,
+            src.named,
+            on_conflict,
+            |t: &Field| t.ident.as_ref(),
+            "field",
+        )?,
+        (Fields::Unnamed(dest), Fields::Unnamed(src)) => dest.unnamed.extend(src.unnamed),
+        // A unit struct is promoted to the style of the other one.
+        (dest @ Fields::Unit, src) => *dest = src,
+        (_, Fields::Unit) => {}
+        (Fields::Named(_), src) => {
+            return Err(syn::Error::new_spanned(src, "expected named fields"));
+        }
+        (Fields::Unnamed(_), src) => {
+            return Err(syn::Error::new_spanned(src, "expected unnamed fields"));
+        }
+    }
+    Ok(())
+}
+
+/// Appends items of `src` to `dest`, items with the same name are resolved by
+/// the conflict policy.
+fn extend_unique<T>(
+    dest: &mut Punctuated<T, Token![,]>,
+    src: Punctuated<T, Token![,]>,
+    on_conflict: Conflict,
+    name: fn(&T) -> Option<&Ident>,
+    kind: &str,
+) -> Result<()> {
+    let mut error = None::<syn::Error>;
+    for item in src {
+        let exist = name(&item).and_then(|ident| dest.iter().position(|t| name(t) == Some(ident)));
+        match (exist, on_conflict) {
+            (None, _) => dest.push(item),
+            (Some(_), Conflict::Keep) => {}
+            (Some(i), Conflict::Replace) => dest[i] = item,
+            (Some(i), Conflict::Error) => {
+                let (ident, exist) = (name(&item).unwrap(), name(&dest[i]).unwrap());
+                let mut e = syn::Error::new(ident.span(), format!("duplicated {kind} `{ident}`"));
+                e.combine(syn::Error::new(
+                    exist.span(),
+                    format!("{kind} `{exist}` is first defined here"),
+                ));
+                combine_error(&mut error, e);
+            }
+        }
+    }
+    error.map_or(Ok(()), Err)
+}
+
+/// Merges attributes into `dest`, identical attributes and derived traits
+/// which already exist are skipped.
+fn merge_attrs(dest: &mut Vec<Attribute>, src: Vec<Attribute>) -> Result<()> {
+    let mut derived = Vec::new();
+    for attr in dest.iter().filter(|t| t.path.is_ident("derive")) {
+        derived.extend(
+            parse_derive(attr)?
+                .into_iter()
+                .map(|t| t.to_token_stream().to_string()),
+        );
+    }
+    for mut attr in src {
+        if attr.path.is_ident("derive") {
+            let paths = parse_derive(&attr)?
+                .into_iter()
+                .filter(|t| {
+                    let s = t.to_token_stream().to_string();
+                    if derived.contains(&s) {
+                        false
+                    } else {
+                        derived.push(s);
+                        true
+                    }
+                })
+                .collect::<Punctuated<_, Token![,]>>();
+            if paths.is_empty() {
+                continue;
+            }
+            attr.tokens = quote!((#paths));
+            dest.push(attr);
+        } else {
+            let s = attr.to_token_stream().to_string();
+            if dest.iter().all(|t| t.to_token_stream().to_string() != s) {
+                dest.push(attr);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn data_kind(data: &Data) -> &'static str {
+    match data {
+        Data::Struct(_) => "a struct",
+        Data::Enum(_) => "an enum",
+        Data::Union(_) => "a union",
+    }
+}
+
 pub enum ExtendArgs {
-    Path(Path),
     As(ExtendAs),
-    Struct(ExtendStruct),
+    Sources(ExtendSources),
 }
 
 impl Parse for ExtendArgs {
     fn parse(input: ParseStream) -> Result<Self> {
-        if input.peek(Token![struct]) {
-            input.parse().map(Self::Struct)
-        } else if input.peek(Token![as]) {
+        if input.peek(Token![as]) {
             input.parse().map(Self::As)
         } else {
-            input.parse().map(Self::Path)
+            input.parse().map(Self::Sources)
         }
     }
 }
 
+#[allow(dead_code)]
 pub struct ExtendAs {
     pub as_token: Token![as],
     pub data: DeriveInput,
+    pub comma_token: Option<Token![,]>,
+    pub options: ExtendOptions,
 }
 
 impl Parse for ExtendAs {
@@ -69,10 +236,61 @@ impl Parse for ExtendAs {
         Ok(Self {
             as_token: input.parse()?,
             data: input.parse()?,
+            comma_token: input.parse()?,
+            options: input.parse()?,
         })
     }
 }
 
+pub struct ExtendSources {
+    pub sources: Punctuated<ExtendSource, Token![,]>,
+    pub options: ExtendOptions,
+}
+
+impl Parse for ExtendSources {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut sources = Punctuated::new();
+        while !input.is_empty() && !ExtendOptions::peek(input) {
+            sources.push_value(input.parse()?);
+            if input.is_empty() {
+                break;
+            }
+            sources.push_punct(input.parse()?);
+        }
+        if sources.is_empty() {
+            return Err(input.error("expected at least one type to extend with"));
+        }
+        Ok(Self {
+            sources,
+            options: input.parse()?,
+        })
+    }
+}
+
+pub enum ExtendSource {
+    Path(Path),
+    Struct(ExtendStruct),
+}
+
+impl Parse for ExtendSource {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Token![struct]) {
+            input.parse().map(Self::Struct)
+        } else {
+            input.parse().map(Self::Path)
+        }
+    }
+}
+
+impl ToTokens for ExtendSource {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Self::Path(t) => t.to_tokens(tokens),
+            Self::Struct(t) => t.to_tokens(tokens),
+        }
+    }
+}
+
 pub struct ExtendStruct {
     pub struct_token: Token![struct],
     pub fields: Fields,
@@ -86,7 +304,7 @@ impl Parse for ExtendStruct {
                 let lookahead = input.lookahead1();
                 if lookahead.peek(token::Paren) {
                     Fields::Unnamed(input.parse()?)
-                } else if input.peek(token::Brace) {
+                } else if lookahead.peek(token::Brace) {
                     Fields::Named(input.parse()?)
                 } else {
                     return Err(lookahead.error());
@@ -95,3 +313,97 @@ impl Parse for ExtendStruct {
         })
     }
 }
+
+impl ToTokens for ExtendStruct {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.struct_token.to_tokens(tokens);
+        self.fields.to_tokens(tokens);
+    }
+}
+
+/// Options of `extend`, e.g. `on_conflict = keep, attrs = merge`.
+#[derive(Default)]
+pub struct ExtendOptions {
+    pub on_conflict: Conflict,
+    pub merge_attrs: bool,
+}
+
+impl ExtendOptions {
+    fn peek(input: ParseStream) -> bool {
+        (input.peek(kw::on_conflict) || input.peek(kw::attrs)) && input.peek2(Token![=])
+    }
+}
+
+impl Parse for ExtendOptions {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut options = Self::default();
+        while !input.is_empty() {
+            let lookahead = input.lookahead1();
+            if lookahead.peek(kw::on_conflict) {
+                input.parse::<kw::on_conflict>()?;
+                input.parse::<Token![=]>()?;
+                options.on_conflict = input.parse()?;
+            } else if lookahead.peek(kw::attrs) {
+                input.parse::<kw::attrs>()?;
+                input.parse::<Token![=]>()?;
+                let value = input.parse::<Ident>()?;
+                options.merge_attrs = match value.to_string().as_str() {
+                    "merge" => true,
+                    "drop" => false,
+                    _ => return Err(syn::Error::new(value.span(), "expected `merge` or `drop`")),
+                };
+            } else {
+                return Err(lookahead.error());
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(options)
+    }
+}
+
+impl ToTokens for ExtendOptions {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let on_conflict = match self.on_conflict {
+            Conflict::Error => quote!(error),
+            Conflict::Keep => quote!(keep),
+            Conflict::Replace => quote!(replace),
+        };
+        let attrs = if self.merge_attrs {
+            quote!(merge)
+        } else {
+            quote!(drop)
+        };
+        tokens.extend(quote!(on_conflict = #on_conflict, attrs = #attrs));
+    }
+}
+
+/// How to resolve a field or variant which exists in both types.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum Conflict {
+    /// Reports an error pointing to both definitions.
+    #[default]
+    Error,
+    /// Keeps the existing one.
+    Keep,
+    /// Replaces the existing one with the new one.
+    Replace,
+}
+
+impl Parse for Conflict {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let value = input.parse::<Ident>()?;
+        Ok(match value.to_string().as_str() {
+            "error" => Self::Error,
+            "keep" => Self::Keep,
+            "replace" => Self::Replace,
+            _ => {
+                return Err(syn::Error::new(
+                    value.span(),
+                    "expected one of `error`, `keep` or `replace`",
+                ))
+            }
+        })
+    }
+}
@@ -0,0 +1,285 @@
+use super::{ast::DeriveInputExt, kw};
+use crate::{TransformRest, TransformState, Transformer};
+use proc_macro2::Span;
+use syn::{
+    braced,
+    parse::{Nothing, Parse, ParseStream},
+    token, DeriveInput, Ident, LitStr, Result, Token,
+};
+
+pub(crate) struct Rename;
+
+impl Transformer for Rename {
+    type Args = NameTemplate;
+
+    fn transform(
+        mut data: DeriveInput,
+        name: Self::Args,
+        _: &mut TransformRest,
+    ) -> Result<TransformState> {
+        data.ident = name.expand(&data.ident)?;
+        Ok(TransformState::pipe(data).build())
+    }
+}
+
+/// A name like `Name`, or a template like `{}Patch` in which `{}` is replaced
+/// by the current name.
+pub struct NameTemplate {
+    pub span: Span,
+    parts: Vec<Option<Ident>>,
+}
+
+impl NameTemplate {
+    pub fn expand(&self, current: &Ident) -> Result<Ident> {
+        if let [Some(name)] = self.parts.as_slice() {
+            return Ok(name.clone());
+        }
+        let current = current.to_string();
+        let current = current.strip_prefix("r#").unwrap_or(&current);
+        let name = self
+            .parts
+            .iter()
+            .map(|t| match t {
+                Some(t) => t.to_string(),
+                None => current.to_owned(),
+            })
+            .collect::<String>();
+        syn::parse_str::<Ident>(&name)
+            .map(|mut t| {
+                t.set_span(self.span);
+                t
+            })
+            .map_err(|_| syn::Error::new(self.span, format!("invalid identifier `{name}`")))
+    }
+}
+
+impl Parse for NameTemplate {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.span();
+        let mut parts = Vec::new();
+        while !input.is_empty() {
+            let lookahead = input.lookahead1();
+            if lookahead.peek(token::Brace) {
+                let content;
+                braced!(content in input);
+                content.parse::<Nothing>()?;
+                parts.push(None);
+            } else if lookahead.peek(Ident) && !matches!(parts.last(), Some(Some(_))) {
+                parts.push(Some(input.parse()?));
+            } else {
+                return Err(lookahead.error());
+            }
+        }
+        if parts.is_empty() {
+            return Err(input.error("expected a name or a template like `{}Patch`"));
+        }
+        Ok(Self { span, parts })
+    }
+}
+
+pub(crate) struct RenameFields;
+
+impl Transformer for RenameFields {
+    type Args = RenameArgs;
+
+    fn transform(
+        mut data: DeriveInput,
+        args: Self::Args,
+        _: &mut TransformRest,
+    ) -> Result<TransformState> {
+        for field in data.fields_iter().flat_map(|fields| fields.iter_mut()) {
+            if let Some(ident) = &mut field.ident {
+                *ident = args.rename(ident)?;
+            }
+        }
+        Ok(TransformState::pipe(data).build())
+    }
+}
+
+pub(crate) struct RenameVariants;
+
+impl Transformer for RenameVariants {
+    type Args = RenameArgs;
+
+    fn transform(
+        mut data: DeriveInput,
+        args: Self::Args,
+        rest: &mut TransformRest,
+    ) -> Result<TransformState> {
+        let variants = data
+            .variants_mut()
+            .ok_or_else(|| syn::Error::new(rest.span(), "only enum has variants"))?;
+        for variant in variants.iter_mut() {
+            variant.ident = args.rename(&variant.ident)?;
+        }
+        Ok(TransformState::pipe(data).build())
+    }
+}
+
+/// Arguments of `rename_fields` and `rename_variants`, e.g.
+/// `case = camel, prefix = "db_"`. The case conversion is applied before
+/// adding the prefix and the suffix.
+#[derive(Default)]
+pub struct RenameArgs {
+    pub case: Option<Case>,
+    pub prefix: Option<LitStr>,
+    pub suffix: Option<LitStr>,
+}
+
+impl RenameArgs {
+    fn rename(&self, ident: &Ident) -> Result<Ident> {
+        let name = ident.to_string();
+        let name = name.strip_prefix("r#").unwrap_or(&name);
+        let mut renamed = match self.case {
+            Some(case) => case.convert(name),
+            None => name.to_owned(),
+        };
+        if let Some(prefix) = &self.prefix {
+            renamed.insert_str(0, &prefix.value());
+        }
+        if let Some(suffix) = &self.suffix {
+            renamed.push_str(&suffix.value());
+        }
+        syn::parse_str::<Ident>(&renamed)
+            .map(|mut t| {
+                t.set_span(ident.span());
+                t
+            })
+            .map_err(|_| {
+                syn::Error::new(
+                    ident.span(),
+                    format!("`{ident}` is renamed to an invalid identifier `{renamed}`"),
+                )
+            })
+    }
+}
+
+impl Parse for RenameArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        fn set<T>(arg: &mut Option<T>, span: Span, value: T) -> Result<()> {
+            if arg.is_some() {
+                return Err(syn::Error::new(span, "duplicated argument"));
+            }
+            *arg = Some(value);
+            Ok(())
+        }
+
+        let mut args = Self::default();
+        while !input.is_empty() {
+            let span = input.span();
+            let lookahead = input.lookahead1();
+            if lookahead.peek(kw::case) {
+                input.parse::<kw::case>()?;
+                input.parse::<Token![=]>()?;
+                set(&mut args.case, span, input.parse()?)?;
+            } else if lookahead.peek(kw::prefix) {
+                input.parse::<kw::prefix>()?;
+                input.parse::<Token![=]>()?;
+                set(&mut args.prefix, span, input.parse()?)?;
+            } else if lookahead.peek(kw::suffix) {
+                input.parse::<kw::suffix>()?;
+                input.parse::<Token![=]>()?;
+                set(&mut args.suffix, span, input.parse()?)?;
+            } else {
+                return Err(lookahead.error());
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(args)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Case {
+    /// `lowercase`
+    Lower,
+    /// `UPPERCASE`
+    Upper,
+    /// `snake_case`
+    Snake,
+    /// `SCREAMING_SNAKE_CASE`
+    ScreamingSnake,
+    /// `camelCase`
+    Camel,
+    /// `PascalCase`
+    Pascal,
+}
+
+impl Case {
+    pub fn convert(self, name: &str) -> String {
+        let words = split_words(name);
+        match self {
+            Case::Lower => words.concat().to_lowercase(),
+            Case::Upper => words.concat().to_uppercase(),
+            Case::Snake => words.join("_").to_lowercase(),
+            Case::ScreamingSnake => words.join("_").to_uppercase(),
+            Case::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, t)| {
+                    if i == 0 {
+                        t.to_lowercase()
+                    } else {
+                        capitalize(t)
+                    }
+                })
+                .collect(),
+            Case::Pascal => words.iter().map(|t| capitalize(t)).collect(),
+        }
+    }
+}
+
+impl Parse for Case {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let value = input.parse::<Ident>()?;
+        Ok(match value.to_string().as_str() {
+            "lower" => Self::Lower,
+            "upper" => Self::Upper,
+            "snake" => Self::Snake,
+            "screaming_snake" => Self::ScreamingSnake,
+            "camel" => Self::Camel,
+            "pascal" => Self::Pascal,
+            _ => return Err(syn::Error::new(
+                value.span(),
+                "expected one of `lower`, `upper`, `snake`, `screaming_snake`, `camel` or `pascal`",
+            )),
+        })
+    }
+}
+
+/// Splits a name into words at underscores and case boundaries, e.g.
+/// `HTTPServerError` into `HTTP`, `Server` and `Error`.
+fn split_words(name: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    for part in name.split('_').filter(|t| !t.is_empty()) {
+        let chars = part.char_indices().collect::<Vec<_>>();
+        let mut start = 0;
+        for (i, &(pos, ch)) in chars.iter().enumerate().skip(1) {
+            let prev = chars[i - 1].1;
+            let next = chars.get(i + 1).map(|t| t.1);
+            let boundary = ch.is_uppercase()
+                && (prev.is_lowercase()
+                    || prev.is_numeric()
+                    || prev.is_uppercase() && matches!(next, Some(c) if c.is_lowercase()));
+            if boundary {
+                words.push(&part[start..pos]);
+                start = pos;
+            }
+        }
+        words.push(&part[start..]);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
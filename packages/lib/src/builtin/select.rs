@@ -1,4 +1,4 @@
-use super::ast::{DeriveInputExt, PathExt, Selectors};
+use super::ast::{is_marker, DeriveInputExt, PathExt, Selectors};
 use crate::{TransformRest, TransformState, Transformer};
 use syn::{punctuated::Punctuated, Attribute, DeriveInput, Field, Result, Token};
 
@@ -13,7 +13,83 @@ impl Transformer for Select {
         _: &mut TransformRest,
     ) -> Result<TransformState> {
         data.fields_iter()
-            .for_each(|fields| args.select_fields(fields));
+            .try_for_each(|fields| args.select_fields(fields))?;
+        Ok(TransformState::pipe(data).build())
+    }
+}
+
+pub(crate) struct Omit;
+
+impl Transformer for Omit {
+    type Args = Selectors;
+
+    fn transform(
+        mut data: DeriveInput,
+        args: Self::Args,
+        _: &mut TransformRest,
+    ) -> Result<TransformState> {
+        args.ensure_no_rename()?;
+        let names = data
+            .fields_iter()
+            .flat_map(|fields| fields.iter())
+            .filter_map(|field| field.ident.clone())
+            .collect::<Vec<_>>();
+        args.ensure_exist("field", names.iter())?;
+        data.fields_iter().for_each(|fields| {
+            *fields = std::mem::take(fields)
+                .into_iter()
+                .filter(|field| !args.matches_field(field))
+                .collect();
+        });
+        Ok(TransformState::pipe(data).build())
+    }
+}
+
+pub(crate) struct SelectVariant;
+
+impl Transformer for SelectVariant {
+    type Args = Selectors;
+
+    fn transform(
+        mut data: DeriveInput,
+        args: Self::Args,
+        rest: &mut TransformRest,
+    ) -> Result<TransformState> {
+        let variants = data
+            .variants_mut()
+            .ok_or_else(|| syn::Error::new(rest.span(), "only enum is supported"))?;
+        let mut selected = Punctuated::new();
+        for mut variant in std::mem::take(variants) {
+            if let Some(ident) = args.select_variant(&variant)? {
+                variant.ident = ident;
+                args.strip_markers(&mut variant.attrs);
+                selected.push(variant);
+            }
+        }
+        *variants = selected;
+        Ok(TransformState::pipe(data).build())
+    }
+}
+
+pub(crate) struct OmitVariant;
+
+impl Transformer for OmitVariant {
+    type Args = Selectors;
+
+    fn transform(
+        mut data: DeriveInput,
+        args: Self::Args,
+        rest: &mut TransformRest,
+    ) -> Result<TransformState> {
+        args.ensure_no_rename()?;
+        let variants = data
+            .variants_mut()
+            .ok_or_else(|| syn::Error::new(rest.span(), "only enum is supported"))?;
+        args.ensure_exist("variant", variants.iter().map(|t| &t.ident))?;
+        *variants = std::mem::take(variants)
+            .into_iter()
+            .filter(|variant| !args.matches_variant(variant))
+            .collect();
         Ok(TransformState::pipe(data).build())
     }
 }
@@ -28,41 +104,45 @@ impl Transformer for SelectAttr {
         args: Self::Args,
         _: &mut TransformRest,
     ) -> Result<TransformState> {
-        args.select_attrs(&mut data.attrs);
+        args.select_attrs(&mut data.attrs)?;
         data.fields_iter()
             .flat_map(|fields| fields.iter_mut())
-            .for_each(|field| args.select_attrs(&mut field.attrs));
+            .try_for_each(|field| args.select_attrs(&mut field.attrs))?;
         Ok(TransformState::pipe(data).build())
     }
 }
 
 impl Selectors {
-    fn select_fields(&self, fields: &mut Punctuated<Field, Token![,]>) {
-        *fields = std::mem::take(fields)
-            .into_iter()
-            .filter_map(|mut field| {
-                if let Some(name) = &mut field.ident {
-                    if let Some(rename) = self.select(name) {
-                        *name = rename;
-                    } else {
-                        return None;
-                    }
-                }
-                Some(field)
-            })
-            .collect();
+    fn select_fields(&self, fields: &mut Punctuated<Field, Token![,]>) -> Result<()> {
+        let mut selected = Punctuated::new();
+        for mut field in std::mem::take(fields) {
+            if let Some(ident) = self.select_field(&field)? {
+                field.ident = ident;
+                self.strip_markers(&mut field.attrs);
+                selected.push(field);
+            }
+        }
+        *fields = selected;
+        Ok(())
     }
 
-    fn select_attrs(&self, attrs: &mut Vec<Attribute>) {
-        attrs.retain_mut(|attr| {
+    fn select_attrs(&self, attrs: &mut Vec<Attribute>) -> Result<()> {
+        let mut selected = Vec::with_capacity(attrs.len());
+        for mut attr in std::mem::take(attrs) {
+            if is_marker(&attr) {
+                // Markers are kept for later commands.
+                selected.push(attr);
+                continue;
+            }
             if let Some(name) = attr.path.get_ident_mut() {
-                if let Some(rename) = self.select(name) {
-                    *name = rename;
-                } else {
-                    return false;
+                match self.select(name)? {
+                    Some(rename) => *name = rename,
+                    None => continue,
                 }
             }
-            true
-        });
+            selected.push(attr);
+        }
+        *attrs = selected;
+        Ok(())
     }
 }
@@ -0,0 +1,67 @@
+use super::{ast::Target, kw};
+use crate::{TransformRest, TransformState, Transformer};
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Data, DeriveInput, Result, Token, Visibility,
+};
+
+pub(crate) struct Vis;
+
+impl Transformer for Vis {
+    type Args = VisArgs;
+
+    fn transform(
+        mut data: DeriveInput,
+        args: Self::Args,
+        rest: &mut TransformRest,
+    ) -> Result<TransformState> {
+        for VisItem { target, vis } in args.0 {
+            match target {
+                Target::Type => data.vis = vis,
+                Target::Fields(kw::fields { span }, _) | Target::Field(kw::field { span }, ..)
+                    if matches!(data.data, Data::Enum(_)) =>
+                {
+                    return Err(syn::Error::new(
+                        span,
+                        "fields of enum variants cannot have visibility",
+                    ))
+                }
+                Target::Fields(..) | Target::Field(..) => {
+                    target.for_each_field(&mut data, |field| field.vis = vis.clone())?
+                }
+                Target::Variants(..) | Target::Variant(..) => {
+                    return Err(syn::Error::new(
+                        rest.span(),
+                        "variants cannot have visibility",
+                    ))
+                }
+            }
+        }
+        Ok(TransformState::pipe(data).build())
+    }
+}
+
+/// Arguments of `vis`, e.g. `pub(crate), fields: pub, field id: pub(crate)`.
+pub struct VisArgs(pub Punctuated<VisItem, Token![,]>);
+
+impl Parse for VisArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Punctuated::parse_terminated(input).map(Self)
+    }
+}
+
+/// A visibility applied to the target, an empty one means private.
+pub struct VisItem {
+    pub target: Target,
+    pub vis: Visibility,
+}
+
+impl Parse for VisItem {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            target: input.parse()?,
+            vis: input.parse()?,
+        })
+    }
+}
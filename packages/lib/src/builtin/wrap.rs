@@ -1,89 +1,1024 @@
-use super::ast::Delimiter;
+use super::{
+    ast::{
+        combine_error, field_member, into_marker, match_type, origin_member, origin_variant,
+        pop_wrapped, take_marker, wrapped_layers, Delimiter, DeriveInputExt, GenericsExt, TypeExt,
+    },
+    kw,
+};
 use crate::{TransformRest, TransformState, Transformer};
-use proc_macro2::TokenStream;
-use quote::quote_spanned;
-use syn::{parse_quote, Data, DeriveInput, Ident, Member, Result, Type};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote_spanned, ToTokens};
+use syn::{
+    bracketed,
+    parse::{Parse, ParseStream},
+    parse_quote,
+    punctuated::Punctuated,
+    Data, DataEnum, DeriveInput, Field, Fields, GenericArgument, Generics, Ident, Lifetime, Member,
+    PathArguments, Result, Token, Type, Variant,
+};
 
 pub(crate) struct Wrap;
 
 impl Transformer for Wrap {
-    type Args = Ident;
+    type Args = WrapArgs;
 
     fn transform(
         mut data: DeriveInput,
-        name: Self::Args,
+        args: Self::Args,
         _: &mut TransformRest,
     ) -> Result<TransformState> {
-        match &mut data.data {
-            Data::Struct(data) => {
-                for field in data.fields.iter_mut() {
-                    if let Type::Path(ty) = &field.ty {
-                        if ty.path.leading_colon.is_none()
-                            && ty.path.segments.first().map(|t| &t.ident) == Some(&name)
-                        {
-                        } else {
-                            let inner = &field.ty;
-                            field.ty = parse_quote!(#name::<#inner>);
-                        }
-                    }
+        args.ensure_exist(&mut data)?;
+        let mut wrapped = false;
+        for fields in data.fields_iter() {
+            for (i, field) in fields.iter_mut().enumerate() {
+                let nowrap = take_marker(&mut field.attrs, "nowrap")?;
+                if nowrap || !args.includes(i, field) || match_type(&args.wrapper.0, &field.ty) {
+                    continue;
                 }
+                field.ty = args.wrapper.wrap(&field.ty);
+                field.attrs.push(parse_quote!(#[transtype(wrapped)]));
+                wrapped = true;
             }
-            _ => {
-                return Err(syn::Error::new_spanned(
-                    &data,
-                    "only struct is supported now",
-                ))
-            }
+        }
+        if wrapped {
+            data.generics.merge(args.wrapper.generics());
         }
 
         Ok(TransformState::pipe(data).build())
     }
 }
 
+/// Arguments of `wrap`, e.g. `Option, only = [a, b]` or `Option, except = [id]`.
+pub struct WrapArgs {
+    pub wrapper: Template,
+    pub only: Option<Punctuated<Member, Token![,]>>,
+    pub except: Punctuated<Member, Token![,]>,
+}
+
+impl WrapArgs {
+    /// Returns whether the `i`-th field is selected to be wrapped.
+    fn includes(&self, i: usize, field: &Field) -> bool {
+        let matches = |members: &Punctuated<Member, Token![,]>| {
+            members.iter().any(|member| match_member(member, i, field))
+        };
+        self.only.as_ref().is_none_or(matches) && !matches(&self.except)
+    }
+
+    /// Ensures every listed field exists, in any variant for an enum.
+    fn ensure_exist(&self, data: &mut DeriveInput) -> Result<()> {
+        let mut error = None::<syn::Error>;
+        for member in self.only.iter().flatten().chain(self.except.iter()) {
+            let exist = data.fields_iter().any(|fields| {
+                fields
+                    .iter()
+                    .enumerate()
+                    .any(|(i, field)| match_member(member, i, field))
+            });
+            if !exist {
+                let e = syn::Error::new_spanned(
+                    member,
+                    format!("no field named `{}`", member.to_token_stream()),
+                );
+                combine_error(&mut error, e);
+            }
+        }
+        error.map_or(Ok(()), Err)
+    }
+}
+
+fn match_member(member: &Member, i: usize, field: &Field) -> bool {
+    match (member, &field.ident) {
+        (Member::Named(name), Some(ident)) => name == ident,
+        (Member::Unnamed(index), None) => index.index as usize == i,
+        _ => false,
+    }
+}
+
+impl Parse for WrapArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut args = Self {
+            wrapper: input.parse()?,
+            only: None,
+            except: Punctuated::new(),
+        };
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let lookahead = input.lookahead1();
+            let members = if lookahead.peek(kw::only) {
+                input.parse::<kw::only>()?;
+                args.only.get_or_insert_with(Punctuated::new)
+            } else if lookahead.peek(kw::except) {
+                input.parse::<kw::except>()?;
+                &mut args.except
+            } else {
+                return Err(lookahead.error());
+            };
+            input.parse::<Token![=]>()?;
+            let content;
+            bracketed!(content in input);
+            members.extend(Punctuated::<Member, Token![,]>::parse_terminated(&content)?);
+        }
+        Ok(args)
+    }
+}
+
 pub(crate) struct Wrapped;
 
 impl Transformer for Wrapped {
-    type Args = Type;
+    type Args = WrappedArgs;
 
     fn transform(
-        data: DeriveInput,
-        from: Self::Args,
+        mut data: DeriveInput,
+        args: Self::Args,
         rest: &mut TransformRest,
     ) -> Result<TransformState> {
         let span = rest.span();
-        let mut body = TokenStream::default();
-        match &data.data {
-            Data::Struct(data) => {
-                Delimiter::from_feilds(&data.fields).surround(&mut body, |tokens| {
-                    data.fields.iter().enumerate().for_each(|(i, field)| {
-                        tokens.extend(
-                            field
-                                .ident
-                                .as_ref()
-                                .map(
-                                    |name| quote_spanned!(span=> #name: ::transtype::Wrapper::unwrap(self.#name),),
-                                )
-                                .unwrap_or_else(|| {
-                                    let i = Member::Unnamed(i.into());
-                                    quote_spanned!(span=> ::transtype::Wrapper::unwrap(self.#i),)
-                                }),
-                        )
-                    });
-                });
-            }
-            _ => unreachable!(),
-        }
+        let (original, from, by_origin) = original_of("wrapped", &data, args, rest)?;
+        let ctor = from.to_expr_path()?;
+        let (unwrap, wrap, fallible) = match (&original.data, &data.data) {
+            (Data::Enum(original), Data::Enum(data)) => {
+                let pairs = pair_variants(original, data, &from, span, by_origin)?;
+                (
+                    unwrap_variants(&pairs, &ctor, span),
+                    wrap_variants(original, &pairs, &ctor, span),
+                    pairs
+                        .iter()
+                        .flat_map(|t| t.fields.iter())
+                        .any(FieldPair::is_fallible),
+                )
+            }
+            _ => {
+                let pairs = pair_data(&original, &data, &from, span, by_origin)?;
+                let mut unwrap = ctor.to_token_stream();
+                unwrap.extend(unwrap_fields(
+                    &original_fields(&original),
+                    &pairs,
+                    |pair| {
+                        let member = &pair.wrapped_member;
+                        quote_spanned!(span=> self.#member)
+                    },
+                    span,
+                ));
+                let wrap = wrap_fields(
+                    &original_fields(&data),
+                    &pairs,
+                    |_, pair| {
+                        let member = &pair.member;
+                        quote_spanned!(span=> value.#member)
+                    },
+                    span,
+                );
+                (
+                    unwrap,
+                    wrap.map(|t| quote_spanned!(span=> Self #t)),
+                    pairs.iter().any(FieldPair::is_fallible),
+                )
+            }
+        };
+        // Fallible fields are converted by `try_wrapped` instead.
+        let convert = !fallible && record_into(&mut data, &from);
         let name = &data.ident;
-        let extra = quote_spanned!(span=>
-            impl ::transtype::Wrapped for #name {
+        let (impl_generics, ty_generics, where_clause) = data.generics.split_for_impl();
+        let mut extra = quote_spanned!(span=>
+            impl #impl_generics ::transtype::Wrapped for #name #ty_generics #where_clause {
                 type Original = #from;
 
                 fn unwrap(self) -> Self::Original {
-                    #from #body
+                    #unwrap
                 }
             }
         );
+        // Only if every field and variant of the wrapped type comes from the
+        // original one.
+        if let Some(wrap) = wrap {
+            extra.extend(quote_spanned!(span=>
+                impl #impl_generics ::core::convert::From<#from> for #name #ty_generics #where_clause {
+                    fn from(value: #from) -> Self {
+                        #wrap
+                    }
+                }
+            ));
+        }
+        if convert {
+            extra.extend(quote_spanned!(span=>
+                impl #impl_generics ::core::convert::From<#name #ty_generics> for #from #where_clause {
+                    fn from(value: #name #ty_generics) -> Self {
+                        ::transtype::Wrapped::unwrap(value)
+                    }
+                }
+            ));
+        }
         rest.with_extra(extra);
         Ok(TransformState::pipe(data).build())
     }
 }
+
+pub(crate) struct TryWrapped;
+
+impl Transformer for TryWrapped {
+    type Args = WrappedArgs;
+
+    fn transform(
+        mut data: DeriveInput,
+        args: Self::Args,
+        rest: &mut TransformRest,
+    ) -> Result<TransformState> {
+        let span = rest.span();
+        let (original, from, by_origin) = original_of("try_wrapped", &data, args, rest)?;
+        let pairs = pair_data(&original, &data, &from, span, by_origin)?;
+        let fallible = pairs.iter().any(FieldPair::is_fallible);
+        // Without fallible fields `From` is generated, which may be done by
+        // `wrapped` already.
+        let convert = fallible || !is_recorded(&data, &from);
+        let name = &data.ident;
+        let (_, ty_generics, _) = data.generics.split_for_impl();
+        if convert {
+            let extra = try_unwrap_impl(
+                &original_fields(&original),
+                &pairs,
+                &data.generics,
+                quote_spanned!(span=> #from),
+                quote_spanned!(span=> #name #ty_generics),
+                span,
+            )?;
+            rest.with_extra(extra);
+        }
+        if !fallible {
+            record_into(&mut data, &from);
+        }
+        Ok(TransformState::pipe(data).build())
+    }
+}
+
+/// Returns whether `From<Self>` is implemented for `from` already.
+fn is_recorded(data: &DeriveInput, from: &Type) -> bool {
+    let marker = into_marker(from).into_token_stream().to_string();
+    data.attrs
+        .iter()
+        .any(|t| t.to_token_stream().to_string() == marker)
+}
+
+/// Records that `From<Self>` is implemented for `from`, returns `false` if
+/// it's recorded already, e.g. by both `wrapped` and `try_wrapped`.
+fn record_into(data: &mut DeriveInput, from: &Type) -> bool {
+    if is_recorded(data, from) {
+        return false;
+    }
+    data.attrs.push(into_marker(from));
+    true
+}
+
+/// Returns the original type and the path to it, and whether fields are
+/// followed back by their origin. Any other type than the origin is assumed to
+/// have the same fields as the wrapped one without wrappers, which correspond
+/// to each other by name.
+pub(super) fn original_of(
+    cmd: &str,
+    data: &DeriveInput,
+    args: WrappedArgs,
+    rest: &TransformRest,
+) -> Result<(DeriveInput, Type, bool)> {
+    match args {
+        WrappedArgs::Origin => {
+            let (original, from) = origin_of(cmd, rest)?;
+            Ok((original, from, true))
+        }
+        WrappedArgs::Type(from) => match origin_named(&from, rest) {
+            Some(original) => Ok((original.clone(), *from, true)),
+            None => {
+                let mut original = data.clone();
+                original.remove_markers();
+                Ok((original, *from, false))
+            }
+        },
+    }
+}
+
+/// Returns the type which the pipeline starts from, and the path to it.
+fn origin_of(cmd: &str, rest: &TransformRest) -> Result<(DeriveInput, Type)> {
+    let span = rest.span();
+    let origin = rest.origin().ok_or_else(|| {
+        syn::Error::new(
+            span,
+            format!("the original type is unknown here, specify it like `{cmd}(A)`"),
+        )
+    })?;
+    let name = &origin.ident;
+    let (_, ty_generics, _) = origin.generics.split_for_impl();
+    let from = syn::parse2(quote_spanned!(span=> #name #ty_generics))?;
+    Ok((origin.clone(), from))
+}
+
+/// Returns the type which the pipeline starts from if `ty` names it, `&A`
+/// names `A` too.
+fn origin_named<'a>(ty: &Type, rest: &'a TransformRest) -> Option<&'a DeriveInput> {
+    let origin = rest.origin()?;
+    let mut ty = ty;
+    loop {
+        match ty {
+            Type::Group(t) => ty = &t.elem,
+            Type::Reference(t) => ty = &t.elem,
+            _ => break,
+        }
+    }
+    match ty {
+        Type::Path(t) if t.qself.is_none() && t.path.segments.len() == 1 => {
+            (t.path.segments[0].ident == origin.ident).then_some(origin)
+        }
+        _ => None,
+    }
+}
+
+fn original_fields(data: &DeriveInput) -> Fields {
+    match &data.data {
+        Data::Struct(data) => data.fields.clone(),
+        _ => Fields::Unit,
+    }
+}
+
+/// A field of the original type and the corresponding wrapped one.
+struct FieldPair<'a> {
+    member: Member,
+    wrapped_member: Member,
+    original: &'a Field,
+    wrapped: &'a Field,
+    /// Index of the wrapped field.
+    index: usize,
+    /// How many wrappers the field is wrapped in.
+    layers: usize,
+}
+
+impl FieldPair<'_> {
+    /// Returns whether the field was wrapped.
+    fn is_wrapped(&self) -> bool {
+        self.layers > 0
+    }
+
+    /// Returns whether the field is wrapped in `Option` or `Result`, which may
+    /// fail to unwrap.
+    fn is_fallible(&self) -> bool {
+        self.fallible().is_some()
+    }
+
+    /// Returns the type in the wrapper and the error of unwrapping it if the
+    /// field may fail to unwrap, `Missing` for `Option` and `E` for
+    /// `Result<_, E>`.
+    fn fallible(&self) -> Option<(&Type, Type)> {
+        let ty = &self.wrapped.ty;
+        if !self.is_wrapped()
+            || !(match_type(&parse_quote!(Option<_>), ty)
+                || match_type(&parse_quote!(Result<_, _>), ty))
+        {
+            return None;
+        }
+        let mut ty = ty;
+        while let Type::Group(t) = ty {
+            ty = &t.elem;
+        }
+        let segment = match ty {
+            Type::Path(ty) => ty.path.segments.last()?,
+            _ => return None,
+        };
+        let args = match &segment.arguments {
+            PathArguments::AngleBracketed(args) => args
+                .args
+                .iter()
+                .map(|t| match t {
+                    GenericArgument::Type(ty) => Some(ty),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?,
+            _ => return None,
+        };
+        match args[..] {
+            [inner] => Some((inner, parse_quote!(::transtype::Missing))),
+            [inner, error] => Some((inner, error.clone())),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the field, tuple fields are named by index.
+    fn name(&self) -> String {
+        match &self.member {
+            Member::Named(ident) => ident.to_string(),
+            Member::Unnamed(index) => index.index.to_string(),
+        }
+    }
+}
+
+fn pair_data<'a>(
+    original: &'a DeriveInput,
+    data: &'a DeriveInput,
+    from: &Type,
+    span: Span,
+    by_origin: bool,
+) -> Result<Vec<FieldPair<'a>>> {
+    match (&original.data, &data.data) {
+        (Data::Struct(original), Data::Struct(data)) => {
+            pair_fields(&original.fields, &data.fields, from, span, by_origin)
+        }
+        _ => Err(syn::Error::new(
+            span,
+            format!(
+                "cannot convert `{}` back to `{}`",
+                data.ident, original.ident
+            ),
+        )),
+    }
+}
+
+/// Finds the wrapped field for every field of the original type, fields
+/// correspond to each other by their origin if `by_origin` is set, otherwise
+/// by name, or by index for tuple fields.
+fn pair_fields<'a>(
+    original: &'a Fields,
+    fields: &'a Fields,
+    from: &Type,
+    span: Span,
+    by_origin: bool,
+) -> Result<Vec<FieldPair<'a>>> {
+    let mut error = None::<syn::Error>;
+    let mut pairs = Vec::new();
+    for (i, field) in original.iter().enumerate() {
+        let key = field_member(i, field);
+        // Fields which aren't tracked, e.g. added by `extend`, never come from
+        // the origin.
+        let found = fields.iter().enumerate().find(|(k, t)| {
+            if by_origin {
+                origin_member(t).as_ref() == Some(&key)
+            } else {
+                field_member(*k, t) == key
+            }
+        });
+        let member = field_member(i, field);
+        match found {
+            Some((index, wrapped)) => pairs.push(FieldPair {
+                member,
+                wrapped_member: field_member(index, wrapped),
+                original: field,
+                wrapped,
+                index,
+                layers: wrapped_layers(wrapped),
+            }),
+            None => {
+                let e = syn::Error::new(
+                    span,
+                    format!(
+                        "field `{}` of `{}` is missing",
+                        member.to_token_stream(),
+                        from.to_token_stream(),
+                    ),
+                );
+                combine_error(&mut error, e);
+            }
+        }
+    }
+    error.map_or(Ok(pairs), Err)
+}
+
+/// Generates fields of the original type, wrapped fields are unwrapped and
+/// others are moved, `source` returns the expression of a wrapped field.
+fn unwrap_fields(
+    original: &Fields,
+    pairs: &[FieldPair],
+    source: impl Fn(&FieldPair) -> TokenStream,
+    span: Span,
+) -> TokenStream {
+    let mut tokens = TokenStream::default();
+    Delimiter::from_feilds(original).surround(&mut tokens, |tokens| {
+        for pair in pairs {
+            let value = unwrap_layers(source(pair), pair.layers, span);
+            let ident = &pair.original.ident;
+            let colon = ident.as_ref().map(|_| quote_spanned!(span=> :));
+            tokens.extend(quote_spanned!(span=> #ident #colon #value,));
+        }
+    });
+    tokens
+}
+
+/// Removes wrappers from the value one by one, from the outermost one.
+fn unwrap_layers(value: TokenStream, layers: usize, span: Span) -> TokenStream {
+    (0..layers).fold(
+        value,
+        |value, _| quote_spanned!(span=> ::transtype::Wrapper::unwrap(#value)),
+    )
+}
+
+/// Generates fields of the wrapped type, wrapped fields are wrapped and others
+/// are moved, `source` returns the expression of the `i`-th original field.
+/// Returns `None` if any field doesn't exist in the original type.
+fn wrap_fields(
+    wrapped: &Fields,
+    pairs: &[FieldPair],
+    source: impl Fn(usize, &FieldPair) -> TokenStream,
+    span: Span,
+) -> Option<TokenStream> {
+    let mut tokens = TokenStream::default();
+    let mut complete = true;
+    Delimiter::from_feilds(wrapped).surround(&mut tokens, |tokens| {
+        for (i, field) in wrapped.iter().enumerate() {
+            let (k, pair) = match pairs.iter().enumerate().find(|(_, t)| t.index == i) {
+                Some(found) => found,
+                None => {
+                    complete = false;
+                    return;
+                }
+            };
+            let value = (0..pair.layers).fold(
+                source(k, pair),
+                |value, _| quote_spanned!(span=> ::transtype::Wrapper::wrap(#value)),
+            );
+            let ident = &field.ident;
+            let colon = ident.as_ref().map(|_| quote_spanned!(span=> :));
+            tokens.extend(quote_spanned!(span=> #ident #colon #value,));
+        }
+    });
+    complete.then_some(tokens)
+}
+
+/// A variant of the original type and the corresponding wrapped one.
+struct VariantPair<'a> {
+    original: &'a Variant,
+    wrapped: &'a Variant,
+    fields: Vec<FieldPair<'a>>,
+}
+
+/// Finds the original variant for every wrapped variant, by its origin if
+/// `by_origin` is set, otherwise by name.
+fn pair_variants<'a>(
+    original: &'a DataEnum,
+    data: &'a DataEnum,
+    from: &Type,
+    span: Span,
+    by_origin: bool,
+) -> Result<Vec<VariantPair<'a>>> {
+    let mut error = None::<syn::Error>;
+    let mut pairs = Vec::new();
+    for variant in data.variants.iter() {
+        let name = &variant.ident;
+        let key = if by_origin {
+            origin_variant(variant)
+        } else {
+            Some(name.clone())
+        };
+        let found = original
+            .variants
+            .iter()
+            .find(|t| Some(&t.ident) == key.as_ref());
+        let result = match found {
+            Some(found) => {
+                pair_fields(&found.fields, &variant.fields, from, span, by_origin).map(|fields| {
+                    pairs.push(VariantPair {
+                        original: found,
+                        wrapped: variant,
+                        fields,
+                    })
+                })
+            }
+            None => Err(syn::Error::new(
+                span,
+                format!(
+                    "variant `{name}` doesn't exist in `{}`",
+                    from.to_token_stream()
+                ),
+            )),
+        };
+        if let Err(e) = result {
+            combine_error(&mut error, e);
+        }
+    }
+    error.map_or(Ok(pairs), Err)
+}
+
+fn field_var(index: usize, span: Span) -> Ident {
+    format_ident!("__field{}", index, span = span)
+}
+
+/// Generates a pattern which binds fields selected by `bind` to variables.
+fn bind_fields(fields: &Fields, bind: impl Fn(usize) -> bool, span: Span) -> TokenStream {
+    let mut pat = TokenStream::default();
+    Delimiter::from_feilds(fields).surround(&mut pat, |tokens| {
+        for (i, field) in fields.iter().enumerate() {
+            let ident = &field.ident;
+            let colon = ident.as_ref().map(|_| quote_spanned!(span=> :));
+            if bind(i) {
+                let var = field_var(i, span);
+                tokens.extend(quote_spanned!(span=> #ident #colon #var,));
+            } else {
+                tokens.extend(quote_spanned!(span=> #ident #colon _,));
+            }
+        }
+    });
+    pat
+}
+
+/// Generates a match which converts every wrapped variant into the original
+/// one.
+fn unwrap_variants(pairs: &[VariantPair], ctor: &syn::Path, span: Span) -> TokenStream {
+    let mut arms = TokenStream::default();
+    for pair in pairs {
+        let name = &pair.wrapped.ident;
+        let original = &pair.original.ident;
+        let pat = bind_fields(
+            &pair.wrapped.fields,
+            |i| pair.fields.iter().any(|t| t.index == i),
+            span,
+        );
+        let body = unwrap_fields(
+            &pair.original.fields,
+            &pair.fields,
+            |field| field_var(field.index, span).into_token_stream(),
+            span,
+        );
+        arms.extend(quote_spanned!(span=> Self::#name #pat => #ctor::#original #body,));
+    }
+    quote_spanned!(span=> match self { #arms })
+}
+
+/// Generates a match which converts every original variant into the wrapped
+/// one, returns `None` if any of them can't be converted.
+fn wrap_variants(
+    original: &DataEnum,
+    pairs: &[VariantPair],
+    ctor: &syn::Path,
+    span: Span,
+) -> Option<TokenStream> {
+    let mut arms = TokenStream::default();
+    for variant in original.variants.iter() {
+        let pair = pairs.iter().find(|t| t.original.ident == variant.ident)?;
+        let name = &variant.ident;
+        let wrapped = &pair.wrapped.ident;
+        let pat = bind_fields(&variant.fields, |_| true, span);
+        let body = wrap_fields(
+            &pair.wrapped.fields,
+            &pair.fields,
+            |i, _| field_var(i, span).into_token_stream(),
+            span,
+        )?;
+        arms.extend(quote_spanned!(span=> #ctor::#name #pat => Self::#wrapped #body,));
+    }
+    Some(quote_spanned!(span=> match value { #arms }))
+}
+
+fn error_name(ty: &Type) -> String {
+    let name = ty.to_token_stream().to_string();
+    match name.as_str() {
+        ":: transtype :: Missing" => "Missing".to_owned(),
+        _ => name,
+    }
+}
+
+/// Generates `TryFrom<#source> for #target`, in which every fallible field is
+/// unwrapped by `TryWrapper` and failures are collected into `FieldErrors`,
+/// other wrapped fields are unwrapped by `Wrapper`. Generates `From` instead
+/// if no field is fallible.
+fn try_unwrap_impl(
+    original: &Fields,
+    pairs: &[FieldPair],
+    generics: &Generics,
+    target: TokenStream,
+    source: TokenStream,
+    span: Span,
+) -> Result<TokenStream> {
+    let mut error_ty = None::<(Type, &FieldPair)>;
+    let mut unwraps = TokenStream::default();
+    let mut body = TokenStream::default();
+    let mut error = None::<syn::Error>;
+    Delimiter::from_feilds(original).surround(&mut body, |tokens| {
+        for (i, pair) in pairs.iter().enumerate() {
+            let member = &pair.wrapped_member;
+            let ident = &pair.original.ident;
+            let colon = ident.as_ref().map(|_| quote_spanned!(span=> :));
+            let (inner, ty) = match pair.fallible() {
+                Some(fallible) => fallible,
+                None => {
+                    let value = unwrap_layers(quote_spanned!(span=> value.#member), pair.layers, span);
+                    tokens.extend(quote_spanned!(span=> #ident #colon #value,));
+                    continue;
+                }
+            };
+            // All failures are collected into one `FieldErrors`, which holds
+            // only one type of errors.
+            match &error_ty {
+                None => error_ty = Some((ty, pair)),
+                Some((first, first_pair))
+                    if first.to_token_stream().to_string() != ty.to_token_stream().to_string() =>
+                {
+                    let e = syn::Error::new(
+                        span,
+                        format!(
+                            "field `{}` fails with `{}`, but field `{}` fails with `{}`, \
+                             fallible fields must share the same error type",
+                            pair.name(),
+                            error_name(&ty),
+                            first_pair.name(),
+                            error_name(first),
+                        ),
+                    );
+                    combine_error(&mut error, e);
+                }
+                Some(_) => {}
+            }
+            let var = format_ident!("__field{}", i);
+            let name = pair.name();
+            unwraps.extend(quote_spanned!(span=>
+                let #var = ::transtype::private::try_unwrap::<_, #inner>(value.#member, #name, &mut errors);
+            ));
+            // Only the outermost wrapper may fail, inner ones are unwrapped
+            // by `Wrapper`.
+            let value = unwrap_layers(
+                quote_spanned!(span=> ::core::option::Option::unwrap(#var)),
+                pair.layers - 1,
+                span,
+            );
+            tokens.extend(quote_spanned!(span=> #ident #colon #value,));
+        }
+    });
+    if let Some(error) = error {
+        return Err(error);
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    // Without fallible fields, the conversion never fails, `TryFrom` is
+    // implemented by `From`.
+    let error_ty = match error_ty {
+        Some((ty, _)) => ty,
+        None => {
+            return Ok(quote_spanned!(span=>
+                impl #impl_generics ::core::convert::From<#source> for #target #where_clause {
+                    fn from(value: #source) -> Self {
+                        Self #body
+                    }
+                }
+            ))
+        }
+    };
+    Ok(quote_spanned!(span=>
+        impl #impl_generics ::core::convert::TryFrom<#source> for #target #where_clause {
+            type Error = ::transtype::FieldErrors<#error_ty>;
+
+            fn try_from(value: #source) -> ::core::result::Result<Self, Self::Error> {
+                let mut errors = ::transtype::FieldErrors::new();
+                #unwraps
+                if !errors.is_empty() {
+                    return ::core::result::Result::Err(errors);
+                }
+                ::core::result::Result::Ok(Self #body)
+            }
+        }
+    ))
+}
+
+pub enum WrappedArgs {
+    /// The origin of the pipeline.
+    Origin,
+    Type(Box<Type>),
+}
+
+impl Parse for WrappedArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.is_empty() {
+            Ok(Self::Origin)
+        } else {
+            input.parse().map(Box::new).map(Self::Type)
+        }
+    }
+}
+
+pub(crate) struct Unwrap;
+
+impl Transformer for Unwrap {
+    type Args = UnwrapArgs;
+
+    fn transform(
+        mut data: DeriveInput,
+        args: Self::Args,
+        rest: &mut TransformRest,
+    ) -> Result<TransformState> {
+        let wrapped = match (&data.data, &args.from) {
+            (Data::Struct(data), Some(_)) => Some(data.fields.clone()),
+            (_, Some(from)) => {
+                return Err(syn::Error::new_spanned(
+                    from,
+                    "only struct can be converted from the wrapped type",
+                ))
+            }
+            (_, None) => None,
+        };
+        for fields in data.fields_iter() {
+            for field in fields.iter_mut() {
+                if let Some(inner) = args.wrapper.unwrap(&field.ty) {
+                    field.ty = inner.clone();
+                    pop_wrapped(field);
+                }
+            }
+        }
+        if let (Some(from), Some(wrapped)) = (&args.from, wrapped) {
+            let span = rest.span();
+            let fields = original_fields(&data);
+            let mut pairs = pair_fields(&fields, &wrapped, from, span, false)?;
+            // Only fields unwrapped here are wrapped in the source type.
+            for pair in pairs.iter_mut() {
+                pair.layers = usize::from(
+                    pair.original.ty.to_token_stream().to_string()
+                        != pair.wrapped.ty.to_token_stream().to_string(),
+                );
+            }
+            let name = &data.ident;
+            let (_, ty_generics, _) = data.generics.split_for_impl();
+            let extra = try_unwrap_impl(
+                &fields,
+                &pairs,
+                &data.generics,
+                quote_spanned!(span=> #name #ty_generics),
+                quote_spanned!(span=> #from),
+                span,
+            )?;
+            rest.with_extra(extra);
+        }
+        Ok(TransformState::pipe(data).build())
+    }
+}
+
+/// Arguments of `unwrap`, e.g. `Option, from = PartialA`.
+pub struct UnwrapArgs {
+    pub wrapper: Template,
+    pub from: Option<Type>,
+}
+
+impl Parse for UnwrapArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let wrapper = input.parse()?;
+        let mut from = None;
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if !input.is_empty() {
+                input.parse::<kw::from>()?;
+                input.parse::<Token![=]>()?;
+                from = Some(input.parse()?);
+                input.parse::<Option<Token![,]>>()?;
+            }
+        }
+        Ok(Self { wrapper, from })
+    }
+}
+
+/// A wrapper type with a `_` hole, e.g. `Result<_, E>` or `&'a _`, a path
+/// without arguments wraps one type, e.g. `Option` is `Option<_>`.
+pub struct Template(pub Type);
+
+impl Template {
+    /// Fills the hole with the type.
+    fn wrap(&self, ty: &Type) -> Type {
+        let mut wrapped = self.0.clone();
+        walk_type(&mut wrapped, &mut |t| {
+            if let Type::Infer(_) = t {
+                *t = ty.clone();
+            }
+        });
+        wrapped
+    }
+
+    /// Returns the type in the hole if the type matches the template.
+    fn unwrap<'a>(&self, ty: &'a Type) -> Option<&'a Type> {
+        fn find<'a>(pat: &Type, ty: &'a Type) -> Option<&'a Type> {
+            match (pat, ty) {
+                (Type::Infer(_), ty) => Some(ty),
+                (Type::Group(pat), ty) => find(&pat.elem, ty),
+                (pat, Type::Group(ty)) => find(pat, &ty.elem),
+                (Type::Paren(pat), ty) => find(&pat.elem, ty),
+                (pat, Type::Paren(ty)) => find(pat, &ty.elem),
+                (Type::Path(pat), Type::Path(ty)) => pat
+                    .path
+                    .segments
+                    .iter()
+                    .rev()
+                    .zip(ty.path.segments.iter().rev())
+                    .find_map(|(pat, seg)| match (&pat.arguments, &seg.arguments) {
+                        (
+                            PathArguments::AngleBracketed(pat),
+                            PathArguments::AngleBracketed(args),
+                        ) => pat.args.iter().zip(args.args.iter()).find_map(|t| match t {
+                            (GenericArgument::Type(pat), GenericArgument::Type(ty)) => {
+                                find(pat, ty)
+                            }
+                            _ => None,
+                        }),
+                        _ => None,
+                    }),
+                (Type::Reference(pat), Type::Reference(ty)) => find(&pat.elem, &ty.elem),
+                (Type::Ptr(pat), Type::Ptr(ty)) => find(&pat.elem, &ty.elem),
+                (Type::Slice(pat), Type::Slice(ty)) => find(&pat.elem, &ty.elem),
+                (Type::Array(pat), Type::Array(ty)) => find(&pat.elem, &ty.elem),
+                (Type::Tuple(pat), Type::Tuple(ty)) => pat
+                    .elems
+                    .iter()
+                    .zip(ty.elems.iter())
+                    .find_map(|(pat, ty)| find(pat, ty)),
+                _ => None,
+            }
+        }
+
+        if match_type(&self.0, ty) {
+            find(&self.0, ty)
+        } else {
+            None
+        }
+    }
+
+    /// Returns lifetimes used by the template, which are declared on the
+    /// wrapped type.
+    fn generics(&self) -> Generics {
+        let mut lifetimes = Vec::<Lifetime>::new();
+        walk_type(&mut self.0.clone(), &mut |t| {
+            let mut found = Vec::new();
+            match t {
+                Type::Reference(t) => found.extend(t.lifetime.clone()),
+                Type::Path(t) => {
+                    for seg in t.path.segments.iter() {
+                        if let PathArguments::AngleBracketed(args) = &seg.arguments {
+                            found.extend(args.args.iter().filter_map(|arg| match arg {
+                                GenericArgument::Lifetime(lt) => Some(lt.clone()),
+                                _ => None,
+                            }));
+                        }
+                    }
+                }
+                _ => {}
+            }
+            for lt in found {
+                if lt.ident != "static" && lt.ident != "_" && !lifetimes.contains(&lt) {
+                    lifetimes.push(lt);
+                }
+            }
+        });
+        if lifetimes.is_empty() {
+            Generics::default()
+        } else {
+            parse_quote!(<#(#lifetimes),*>)
+        }
+    }
+}
+
+impl Parse for Template {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut ty = input.parse::<Type>()?;
+        let mut holes = 0;
+        walk_type(&mut ty, &mut |t| {
+            if let Type::Infer(_) = t {
+                holes += 1;
+            }
+        });
+        if let Type::Path(t) = &mut ty {
+            match t.path.segments.last_mut() {
+                Some(seg) if holes == 0 && t.qself.is_none() && seg.arguments.is_none() => {
+                    seg.arguments = PathArguments::AngleBracketed(parse_quote!(<_>));
+                    holes = 1;
+                }
+                _ => {}
+            }
+        }
+        match holes {
+            1 => Ok(Self(ty)),
+            0 => Err(syn::Error::new_spanned(
+                ty,
+                "expected a `_` in the wrapper type",
+            )),
+            _ => Err(syn::Error::new_spanned(
+                ty,
+                "expected only one `_` in the wrapper type",
+            )),
+        }
+    }
+}
+
+/// Calls `f` on the type and all types nested in it.
+fn walk_type(ty: &mut Type, f: &mut dyn FnMut(&mut Type)) {
+    f(ty);
+    match ty {
+        Type::Array(t) => walk_type(&mut t.elem, f),
+        Type::Group(t) => walk_type(&mut t.elem, f),
+        Type::Paren(t) => walk_type(&mut t.elem, f),
+        Type::Ptr(t) => walk_type(&mut t.elem, f),
+        Type::Reference(t) => walk_type(&mut t.elem, f),
+        Type::Slice(t) => walk_type(&mut t.elem, f),
+        Type::Tuple(t) => t.elems.iter_mut().for_each(|t| walk_type(t, f)),
+        Type::Path(t) => {
+            if let Some(qself) = &mut t.qself {
+                walk_type(&mut qself.ty, f);
+            }
+            for seg in t.path.segments.iter_mut() {
+                if let PathArguments::AngleBracketed(args) = &mut seg.arguments {
+                    for arg in args.args.iter_mut() {
+                        if let GenericArgument::Type(t) = arg {
+                            walk_type(t, f);
+                        }
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
@@ -8,6 +8,7 @@ mod pipe;
 mod predefined;
 mod transform;
 mod transformer;
+mod wrapper;
 
 use proc_macro2::TokenStream;
 use syn::Result;
@@ -45,7 +46,8 @@ pub mod private {
        define,
        pipe,
        predefined,
-       transform
+       transform,
+       wrapper
     }
 
     pub fn parse_named_arg<T: Parse>(
@@ -82,6 +84,7 @@ mod kw {
     custom_keyword!(extra);
     custom_keyword!(fork);
     custom_keyword!(marker);
+    custom_keyword!(origin);
     custom_keyword!(path);
     custom_keyword!(pipe);
     custom_keyword!(rest);
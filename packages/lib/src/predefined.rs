@@ -1,4 +1,4 @@
-use crate::{kw, NamedArg, TransformRest, TransformState};
+use crate::{builtin::DeriveInputExt, kw, NamedArg, Optional, TransformRest, TransformState};
 use proc_macro2::TokenStream;
 use syn::{
     parse::{Parse, ParseStream},
@@ -6,17 +6,33 @@ use syn::{
 };
 
 pub fn expand(input: TokenStream) -> Result<TokenStream> {
-    let PredefinedInput { args, data, extra } = syn::parse2(input)?;
+    let PredefinedInput {
+        args,
+        data,
+        extra,
+        origin,
+    } = syn::parse2(input)?;
     let PredefinedArgs { rest } = args.content;
     let mut rest = rest.content;
     rest.with_extra(extra.content);
-    TransformState::pipe(data.content).build().transform(rest)
+    let mut data = data.content;
+    match origin.content.into_inner() {
+        // A saved type keeps the origin of the pipeline which produces it.
+        Some(origin) => rest.track_origin(origin),
+        None if rest.origin().is_none() => {
+            rest.track_origin(data.clone());
+            data.mark_origin();
+        }
+        None => {}
+    }
+    TransformState::pipe(data).build().transform(rest)
 }
 
 pub struct PredefinedInput {
     pub args: NamedArg<kw::args, PredefinedArgs>,
     pub data: NamedArg<kw::data, DeriveInput>,
     pub extra: NamedArg<kw::extra, TokenStream>,
+    pub origin: NamedArg<kw::origin, Optional<DeriveInput>>,
 }
 
 impl Parse for PredefinedInput {
@@ -25,6 +41,7 @@ impl Parse for PredefinedInput {
             args: input.parse()?,
             data: input.parse()?,
             extra: input.parse()?,
+            origin: input.parse()?,
         })
     }
 }
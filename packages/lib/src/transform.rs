@@ -197,7 +197,7 @@ define_types! {
         +extra  => with_extra(TokenStream),
         +marker => with_marker(TokenStream),
 
-        /// ```
+        /// ```text
         /// transform! {
         ///     @consume
         ///     data={#data}
@@ -208,7 +208,7 @@ define_types! {
             !data: TokenStream,
         },
 
-        /// ```
+        /// ```text
         /// transform! {
         ///     @debug
         ///     data={#data}
@@ -220,7 +220,7 @@ define_types! {
             !data: DeriveInput,
             ?args: TokenStream,
         },
-        /// ```
+        /// ```text
         /// transform! {
         ///     @fork
         ///     data={#data}
@@ -233,7 +233,7 @@ define_types! {
             ?fork: ListOf<ForkCommand>,
         },
 
-        /// ```
+        /// ```text
         /// transform! {
         ///     @pipe
         ///     data={#data}
@@ -244,7 +244,7 @@ define_types! {
             !data: DeriveInput,
         },
 
-        /// ```
+        /// ```text
         /// transform! {
         ///     @resume
         ///     path={#path}
@@ -255,7 +255,7 @@ define_types! {
             !path: Path,
         },
 
-        /// ```
+        /// ```text
         /// transform! {
         ///     @save
         ///     ...
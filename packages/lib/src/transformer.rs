@@ -1,4 +1,7 @@
-use crate::{builtin, kw, state, ForkCommand, ListOf, NamedArg, PipeCommand, TransformState};
+use crate::{
+    builtin::{self, DeriveInputExt},
+    kw, state, ForkCommand, ListOf, NamedArg, Optional, PipeCommand, TransformState,
+};
 use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote_spanned, ToTokens};
 use std::marker::PhantomData;
@@ -69,6 +72,7 @@ pub struct TransformRest {
     pipe: NamedArg<kw::pipe, ListOf<PipeCommand>>,
     extra: NamedArg<kw::extra, TokenStream>,
     marker: NamedArg<kw::marker, TokenStream>,
+    origin: NamedArg<kw::origin, Optional<DeriveInput>>,
 }
 
 impl TransformRest {
@@ -98,12 +102,24 @@ impl TransformRest {
         self
     }
 
+    /// Get the type which the pipeline starts from, before any command is
+    /// applied.
+    pub fn origin(&self) -> Option<&DeriveInput> {
+        self.origin.content.as_ref()
+    }
+
+    /// Records the origin type, unless the pipeline already has one.
+    pub(crate) fn track_origin(&mut self, origin: DeriveInput) {
+        self.origin.content.get_or_insert(origin);
+    }
+
     pub(crate) fn empty(path: Path) -> Self {
         Self {
             this: NamedArg::new(path),
             pipe: Default::default(),
             extra: Default::default(),
             marker: Default::default(),
+            origin: Default::default(),
         }
     }
 
@@ -128,6 +144,7 @@ impl TransformRest {
             pipe: self.pipe.clone_with(pipe),
             extra: self.extra.clone(),
             marker: self.marker.clone(),
+            origin: self.origin.clone(),
         }
     }
 
@@ -137,6 +154,7 @@ impl TransformRest {
             pipe: self.pipe.take(),
             extra: self.extra.take(),
             marker: self.marker.clone(),
+            origin: self.origin.clone(),
         }
     }
 
@@ -152,6 +170,7 @@ impl Parse for TransformRest {
             pipe: input.parse()?,
             extra: input.parse()?,
             marker: input.parse()?,
+            origin: input.parse()?,
         })
     }
 }
@@ -162,6 +181,7 @@ impl ToTokens for TransformRest {
         self.pipe.to_tokens(tokens);
         self.extra.to_tokens(tokens);
         self.marker.to_tokens(tokens);
+        self.origin.to_tokens(tokens);
     }
 }
 
@@ -253,19 +273,27 @@ fn transform_impl(
                     let output = match execute(cmd, data, rest) {
                         Ok(t) => t,
                         Err(mut e) => {
-                            e.combine(syn::Error::new(
-                                rest.span(),
-                                "an error occurs in this command",
-                            ));
+                            let message = match rest.origin() {
+                                Some(origin) => format!(
+                                    "an error occurs in this command, which transforms `{}`",
+                                    origin.ident
+                                ),
+                                None => "an error occurs in this command".to_owned(),
+                            };
+                            e.combine(syn::Error::new(rest.span(), message));
                             return Err(e);
                         }
                     };
                     match output {
                         ExecuteState::Executed { state: s } => state = s,
-                        ExecuteState::Unsupported { cmd, data } => {
+                        ExecuteState::Unsupported { cmd, mut data } => {
+                            // Custom commands receive the type as it's written,
+                            // so the origin can't be followed after them.
+                            data.remove_tracking();
                             let span = rest.span();
                             let PipeCommand { path, args, .. } = cmd;
-                            let rest = rest.take();
+                            let mut rest = rest.take();
+                            *rest.origin.content = None;
                             state = State::consume(quote_spanned!(span=>
                                 #path! {
                                     data={#data}
@@ -294,6 +322,7 @@ fn transform_impl(
                 let span = rest.span();
                 let name = &data.ident;
                 let extra = rest.take_extra();
+                let origin = &rest.origin.content;
                 state = State::consume(quote_spanned!(span=>
                     macro_rules! #name {
                         ($($args:tt)*) => {
@@ -301,6 +330,7 @@ fn transform_impl(
                                 args={$($args)*}
                                 data={#data}
                                 extra={#extra}
+                                origin={#origin}
                             }
                         };
                     }
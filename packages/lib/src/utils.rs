@@ -151,8 +151,15 @@ where
     }
 }
 
+#[derive(Clone)]
 pub struct Optional<T>(Option<T>);
 
+impl<T> Default for Optional<T> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
 impl<T> Deref for Optional<T> {
     type Target = Option<T>;
 
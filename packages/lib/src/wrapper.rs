@@ -0,0 +1,41 @@
+use proc_macro2::TokenStream;
+use quote::quote_spanned;
+use syn::{spanned::Spanned, Data, DeriveInput, Member, Result};
+
+/// Implements `Wrapper` for a struct with exactly one field, e.g.
+/// `struct Sensitive<T>(T)`.
+pub fn expand(input: TokenStream) -> Result<TokenStream> {
+    let data = syn::parse2::<DeriveInput>(input)?;
+    let span = data.span();
+    let field = match &data.data {
+        Data::Struct(t) if t.fields.len() == 1 => t.fields.iter().next().unwrap(),
+        _ => {
+            return Err(syn::Error::new(
+                span,
+                "`Wrapper` can only be derived for a struct with exactly one field",
+            ))
+        }
+    };
+    let ty = &field.ty;
+    let (wrap, member) = match &field.ident {
+        Some(ident) => (
+            quote_spanned!(span=> fn wrap(#ident: #ty) -> Self { Self { #ident } }),
+            Member::Named(ident.clone()),
+        ),
+        None => (
+            quote_spanned!(span=> fn wrap(value: #ty) -> Self { Self(value) }),
+            Member::Unnamed(0.into()),
+        ),
+    };
+    let name = &data.ident;
+    let (impl_generics, ty_generics, where_clause) = data.generics.split_for_impl();
+    Ok(quote_spanned!(span=>
+        impl #impl_generics ::transtype::Wrapper<#ty> for #name #ty_generics #where_clause {
+            #wrap
+
+            fn unwrap(self) -> #ty {
+                self.#member
+            }
+        }
+    ))
+}
@@ -1,13 +1,34 @@
 #[doc(inline)]
 pub use transtype_impl::*;
 
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    fmt,
+    ops::{Deref, DerefMut},
+    rc::Rc,
+    sync::{Arc, Mutex, PoisonError},
+};
+
 #[doc(hidden)]
 pub mod private {
-    use crate::Wrapper;
+    use crate::{FieldErrors, TryWrapper, Wrapper};
 
     pub enum InnerType {}
 
     pub const fn requires_wrapper<T: Wrapper<InnerType>>() {}
+
+    pub fn try_unwrap<W: TryWrapper<T>, T>(
+        value: W,
+        field: &'static str,
+        errors: &mut FieldErrors<W::Error>,
+    ) -> Option<T> {
+        value.try_unwrap().map_err(|e| errors.push(field, e)).ok()
+    }
+
+    pub fn apply<T, U>(value: T, f: impl FnOnce(T) -> U) -> U {
+        f(value)
+    }
 }
 
 pub trait Wrapper<T> {
@@ -25,8 +46,235 @@ impl<T> Wrapper<T> for Option<T> {
     }
 }
 
+/// A wrapper which adds nothing to the value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Identity<T>(pub T);
+
+impl<T> Deref for Identity<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Identity<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> Wrapper<T> for Identity<T> {
+    fn wrap(value: T) -> Self {
+        Identity(value)
+    }
+
+    fn unwrap(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Wrapper<T> for Box<T> {
+    fn wrap(value: T) -> Self {
+        Box::new(value)
+    }
+
+    fn unwrap(self) -> T {
+        *self
+    }
+}
+
+/// Unwraps the value, which is cloned if it's shared.
+impl<T: Clone> Wrapper<T> for Rc<T> {
+    fn wrap(value: T) -> Self {
+        Rc::new(value)
+    }
+
+    fn unwrap(self) -> T {
+        Rc::try_unwrap(self).unwrap_or_else(|t| T::clone(&t))
+    }
+}
+
+/// Unwraps the value, which is cloned if it's shared.
+impl<T: Clone> Wrapper<T> for Arc<T> {
+    fn wrap(value: T) -> Self {
+        Arc::new(value)
+    }
+
+    fn unwrap(self) -> T {
+        Arc::try_unwrap(self).unwrap_or_else(|t| T::clone(&t))
+    }
+}
+
+impl<T> Wrapper<T> for Cell<T> {
+    fn wrap(value: T) -> Self {
+        Cell::new(value)
+    }
+
+    fn unwrap(self) -> T {
+        self.into_inner()
+    }
+}
+
+impl<T> Wrapper<T> for RefCell<T> {
+    fn wrap(value: T) -> Self {
+        RefCell::new(value)
+    }
+
+    fn unwrap(self) -> T {
+        self.into_inner()
+    }
+}
+
+/// Unwraps the value even if the mutex is poisoned.
+impl<T> Wrapper<T> for Mutex<T> {
+    fn wrap(value: T) -> Self {
+        Mutex::new(value)
+    }
+
+    fn unwrap(self) -> T {
+        self.into_inner().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T: Clone> Wrapper<T> for Cow<'_, T> {
+    fn wrap(value: T) -> Self {
+        Cow::Owned(value)
+    }
+
+    fn unwrap(self) -> T {
+        self.into_owned()
+    }
+}
+
+impl Wrapper<String> for Cow<'_, str> {
+    fn wrap(value: String) -> Self {
+        Cow::Owned(value)
+    }
+
+    fn unwrap(self) -> String {
+        self.into_owned()
+    }
+}
+
+impl<T: Clone> Wrapper<Vec<T>> for Cow<'_, [T]> {
+    fn wrap(value: Vec<T>) -> Self {
+        Cow::Owned(value)
+    }
+
+    fn unwrap(self) -> Vec<T> {
+        self.into_owned()
+    }
+}
+
+/// A wrapper which may fail to unwrap, e.g. an `Option` which is `None`.
+pub trait TryWrapper<T>: Sized {
+    type Error;
+
+    fn try_unwrap(self) -> Result<T, Self::Error>;
+}
+
+impl<T> TryWrapper<T> for Option<T> {
+    type Error = Missing;
+
+    fn try_unwrap(self) -> Result<T, Self::Error> {
+        self.ok_or(Missing)
+    }
+}
+
+impl<T, E> TryWrapper<T> for Result<T, E> {
+    type Error = E;
+
+    fn try_unwrap(self) -> Result<T, Self::Error> {
+        self
+    }
+}
+
 pub trait Wrapped: Sized {
     type Original;
 
     fn unwrap(self) -> Self::Original;
 }
+
+/// Converts a value into one of its wrapped types, e.g.
+/// `let a: WrappedA = a.wrap_into();`.
+pub trait WrapInto: Sized {
+    fn wrap_into<W>(self) -> W
+    where
+        W: Wrapped<Original = Self> + From<Self>,
+    {
+        W::from(self)
+    }
+}
+
+impl<T> WrapInto for T {}
+
+/// The error of unwrapping an empty value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Missing;
+
+impl fmt::Display for Missing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("missing value")
+    }
+}
+
+impl std::error::Error for Missing {}
+
+/// Errors of fields which fail to unwrap, in the order of fields.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldErrors<E = Missing> {
+    errors: Vec<(&'static str, E)>,
+}
+
+impl<E> FieldErrors<E> {
+    pub fn new() -> Self {
+        Self { errors: Vec::new() }
+    }
+
+    pub fn push(&mut self, field: &'static str, error: E) {
+        self.errors.push((field, error));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Returns names of the failed fields, tuple fields are named by index.
+    pub fn fields(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.errors.iter().map(|(field, _)| *field)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &E)> {
+        self.errors.iter().map(|(field, error)| (*field, error))
+    }
+
+    pub fn into_inner(self) -> Vec<(&'static str, E)> {
+        self.errors
+    }
+}
+
+impl<E> Default for FieldErrors<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for FieldErrors<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("failed to unwrap ")?;
+        for (i, (field, error)) in self.errors.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "`{field}` ({error})")?;
+        }
+        Ok(())
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for FieldErrors<E> {}
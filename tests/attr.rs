@@ -0,0 +1,37 @@
+#![allow(unused)]
+
+use transtype::pipe;
+
+#[transtype::define]
+struct A {
+    pub a: String,
+    pub b: usize,
+}
+
+#[transtype::define]
+enum E {
+    X(u8),
+    Y { y: u8 },
+}
+
+pipe! {
+    A
+    -> attr(#[derive(Clone, Default)])
+    -> attr(fields: #[doc = "A field."], field b: #[doc = "The b field."] #[allow(dead_code)])
+    -> finish()
+}
+
+pipe! {
+    E
+    -> attr(#[derive(Debug)], variants: #[doc = "variant"], variant X: #[allow(unused)])
+    -> attr(field 0: #[doc = "first"], field y: #[doc = "y"])
+    -> debug()
+}
+
+#[test]
+fn attr() {
+    let a = A::default().clone();
+    let s = DEBUG_E!().replace(' ', "");
+    assert!(s.contains(r#"#[doc="variant"]#[allow(unused)]X(#[doc="first"]u8)"#));
+    assert!(s.contains(r#"#[doc="variant"]Y{#[doc="y"]y:u8}"#));
+}
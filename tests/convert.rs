@@ -0,0 +1,105 @@
+#![allow(unused)]
+
+use std::collections::HashMap;
+use transtype::pipe;
+
+#[transtype::define]
+#[derive(Debug, PartialEq)]
+struct UserDto {
+    pub id: u32,
+    pub name: String,
+    pub tags: Vec<String>,
+    pub note: String,
+}
+
+#[transtype::define]
+#[derive(Debug, PartialEq)]
+struct User {
+    pub id: u64,
+    pub name: String,
+    pub tags: usize,
+    pub active: bool,
+}
+
+pipe! {
+    User
+    -> finish()
+}
+
+pipe! {
+    UserDto
+    -> convert(into = User, tags = |x| x.len(), default = [active])
+    -> finish()
+}
+
+#[transtype::define]
+struct Labels {
+    pub labels: Vec<(String, u8)>,
+}
+
+#[transtype::define]
+#[derive(Debug, PartialEq)]
+struct LabelMap {
+    pub labels: HashMap<String, u8>,
+}
+
+pipe! {
+    LabelMap
+    -> finish()
+}
+
+pipe! {
+    Labels
+    -> convert(into = LabelMap, labels = |x| HashMap::<String, u8>::from_iter(x.into_iter().rev()))
+    -> finish()
+}
+
+#[transtype::define]
+struct Pair(u8, String);
+
+#[transtype::define]
+#[derive(Debug, PartialEq)]
+struct Wide(u64, String, Option<u8>);
+
+pipe! {
+    Wide
+    -> finish()
+}
+
+pipe! {
+    Pair
+    -> convert(into = Wide, default)
+    -> finish()
+}
+
+#[test]
+fn convert_named() {
+    let dto = UserDto {
+        id: 1,
+        name: "a".into(),
+        tags: vec!["x".into(), "y".into()],
+        note: "dropped".into(),
+    };
+    let expected = User {
+        id: 1,
+        name: "a".into(),
+        tags: 2,
+        active: false,
+    };
+    assert_eq!(User::from(dto), expected);
+}
+
+#[test]
+fn convert_tuple() {
+    let wide = Wide::from(Pair(1, "a".into()));
+    assert_eq!(wide, Wide(1, "a".into(), None));
+}
+
+#[test]
+fn convert_with_generic_override() {
+    let labels = Labels {
+        labels: vec![("a".into(), 1)],
+    };
+    let map = LabelMap::from(labels);
+    assert_eq!(map.labels["a"], 1);
+}
@@ -0,0 +1,65 @@
+#![allow(unused)]
+
+use transtype::pipe;
+
+// Keeps two derive attributes, which rustfmt would merge.
+#[rustfmt::skip]
+#[transtype::define]
+#[derive(Clone, Copy)]
+#[derive(Debug, PartialEq)]
+struct A {
+    pub a: u64,
+    pub b: bool,
+}
+
+#[transtype::define]
+#[derive(Clone, Copy, Debug)]
+enum E {
+    X,
+    Y(u8),
+}
+
+pipe! {
+    A
+    -> derive(+Default, -Copy, -PartialEq, Hash)
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(PlainA)
+    -> derive(-Clone, -Copy, -Debug, -PartialEq)
+    -> finish()
+}
+
+pipe! {
+    E
+    -> derive(-Copy, +PartialEq, +Eq)
+    -> finish()
+}
+
+fn derived(a: A, e: E) -> (A, u64, bool) {
+    let _ = format!("{:?}", a.clone());
+    let default = A::default();
+    (default, PlainA { a: 0, b: false }.a, e == E::X)
+}
+
+#[transtype::define]
+enum V {
+    #[derive(Clone)]
+    X,
+    Y,
+}
+
+pipe! {
+    V
+    -> derive(variants: +Debug)
+    -> debug()
+}
+
+#[test]
+fn derive_variants() {
+    let s = DEBUG_V!().replace(' ', "");
+    assert!(s.contains("#[derive(Clone,Debug)]X"));
+    assert!(s.contains("#[derive(Debug)]Y"));
+}
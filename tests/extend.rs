@@ -19,3 +19,123 @@ pipe! {
     -> extend(B)
     -> finish()
 }
+
+#[transtype::define]
+struct C(pub String);
+
+pipe! {
+    A
+    -> extend(struct {
+        pub created_at: u64,
+        pub updated_at: u64,
+    })
+    -> rename(TimestampedA)
+    -> finish()
+}
+
+pipe! {
+    C
+    -> extend(struct (u32, u32))
+    -> finish()
+}
+
+fn timestamped(a: A, c: C) -> (TimestampedA, C) {
+    (
+        TimestampedA {
+            a: a.a,
+            b: a.b,
+            created_at: 0,
+            updated_at: 0,
+        },
+        C(c.0, 0, 0),
+    )
+}
+
+#[transtype::define]
+struct D(pub usize, pub Option<usize>);
+
+#[transtype::define]
+struct Unit;
+
+pipe! {
+    C
+    -> extend(D)
+    -> rename(CD)
+    -> finish()
+}
+
+pipe! {
+    Unit
+    -> extend(B)
+    -> rename(UnitB)
+    -> finish()
+}
+
+#[transtype::define]
+enum ApiError {
+    NotFound,
+    Unauthorized { reason: String },
+}
+
+#[transtype::define]
+enum DbError {
+    Connection(String),
+    Timeout,
+}
+
+pipe! {
+    ApiError
+    -> extend(DbError)
+    -> finish()
+}
+
+fn extended(cd: CD, b: UnitB) -> [ApiError; 2] {
+    let CD(_, _, _) = cd;
+    let UnitB { c: _, d: _ } = b;
+    [ApiError::NotFound, ApiError::Timeout]
+}
+
+#[transtype::define]
+#[derive(Clone, Debug)]
+struct Base {
+    pub id: u64,
+    pub a: String,
+}
+
+#[transtype::define]
+#[derive(Clone, Default)]
+struct Meta {
+    pub id: String,
+    pub created_at: u64,
+}
+
+pipe! {
+    Base
+    -> extend(Meta, B, struct { pub extra: bool }, on_conflict = keep)
+    -> rename(KeepId)
+    -> finish()
+}
+
+pipe! {
+    Base
+    -> extend(Meta, on_conflict = replace, attrs = merge)
+    -> rename(ReplaceId)
+    -> finish()
+}
+
+fn conflicts(keep: KeepId, replace: ReplaceId) -> (u64, String) {
+    let KeepId {
+        id,
+        a: _,
+        created_at: _,
+        c: _,
+        d: _,
+        extra: _,
+    } = keep.clone();
+    (id, replace.clone().id)
+}
+
+// `Default` only comes from `Meta`, by merging its attributes.
+fn merged_default() -> ReplaceId {
+    ReplaceId::default()
+}
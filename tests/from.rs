@@ -0,0 +1,148 @@
+#![allow(unused)]
+
+use transtype::pipe;
+
+#[transtype::define]
+#[derive(Clone, Debug, PartialEq)]
+struct A {
+    pub id: u64,
+    pub name: String,
+    pub tags: Vec<String>,
+}
+
+pipe! {
+    A
+    -> fork(
+        A={
+            -> finish()
+        }
+        ASummary={
+            -> select(id, name)
+            -> from()
+            -> from(&A)
+            -> finish()
+        }
+        AKey={
+            -> select(id as key)
+            -> from()
+            -> finish()
+        }
+    )
+}
+
+pipe! {
+    A
+    -> rename(ATags)
+    -> select(tags)
+    -> save()
+}
+
+pipe! {
+    ATags
+    -> from(A)
+    -> from(&A)
+    -> finish()
+}
+
+struct NoClone(u8);
+
+#[transtype::define]
+struct Handle {
+    pub id: u64,
+    pub inner: NoClone,
+}
+
+pipe! {
+    Handle
+    -> fork(
+        Handle={
+            -> finish()
+        }
+        Inner={
+            -> select(inner)
+            -> from()
+            -> finish()
+        }
+    )
+}
+
+#[transtype::define]
+struct Point(String, i32, i32);
+
+pipe! {
+    Point
+    -> fork(
+        Point={
+            -> finish()
+        }
+        Flat={
+            -> select(: i32)
+            -> from()
+            -> finish()
+        }
+    )
+}
+
+#[transtype::define]
+struct Keyed {
+    pub key: u64,
+}
+
+pipe! {
+    Keyed
+    -> finish()
+}
+
+// `Keyed` isn't the origin, so fields are matched by name.
+pipe! {
+    A
+    -> rename(KeyedA)
+    -> select(id as key)
+    -> from(Keyed)
+    -> finish()
+}
+
+fn a() -> A {
+    A {
+        id: 1,
+        name: "a".into(),
+        tags: vec!["x".into()],
+    }
+}
+
+#[test]
+fn from_value() {
+    let summary = ASummary::from(a());
+    assert_eq!(summary.id, 1);
+    assert_eq!(summary.name, "a");
+    let inner = Inner::from(Handle {
+        id: 1,
+        inner: NoClone(2),
+    });
+    assert_eq!(inner.inner.0, 2);
+}
+
+#[test]
+fn from_ref() {
+    let a = a();
+    let summary = ASummary::from(&a);
+    assert_eq!(summary.name, a.name);
+    let tags = ATags::from(&a);
+    assert_eq!(tags.tags, a.tags);
+}
+
+#[test]
+fn from_renamed() {
+    assert_eq!(AKey::from(a()).key, 1);
+}
+
+#[test]
+fn from_other_type() {
+    assert_eq!(KeyedA::from(Keyed { key: 2 }).key, 2);
+}
+
+#[test]
+fn from_tuple() {
+    let flat = Flat::from(Point("p".into(), 1, 2));
+    assert_eq!((flat.0, flat.1), (1, 2));
+}
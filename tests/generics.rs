@@ -0,0 +1,75 @@
+#![allow(unused)]
+
+use transtype::pipe;
+
+#[transtype::define]
+#[derive(Clone)]
+struct Page<T: Clone>
+where
+    T: Default,
+{
+    pub items: Vec<T>,
+    pub total: usize,
+}
+
+#[transtype::define]
+struct Envelope<'a, T, const N: usize> {
+    pub body: &'a T,
+    pub tags: [&'a str; N],
+}
+
+pipe! {
+    Page
+    -> fork(
+        Page={
+            -> finish()
+        }
+        WrappedPage={
+            -> wrap(Option)
+            -> wrapped(Page<T>)
+            -> finish()
+        }
+    )
+}
+
+pipe! {
+    Envelope
+    -> extend(Page)
+    -> rename(PagedEnvelope)
+    -> finish()
+}
+
+fn paged<'a>(page: Page<u8>, body: &'a u8) -> PagedEnvelope<'a, u8, 1> {
+    PagedEnvelope {
+        body,
+        tags: ["page"],
+        items: page.items,
+        total: page.total,
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Cell<T: Copy>(T);
+
+#[transtype::define]
+struct Slot<T> {
+    pub id: T,
+}
+
+#[transtype::define]
+struct CopySlot<T: Copy> {
+    pub cell: Cell<T>,
+}
+
+// `T: Copy` of `CopySlot` is required by `Cell<T>`, which is kept even though
+// `Slot` already declares `T`.
+pipe! {
+    Slot
+    -> extend(CopySlot)
+    -> rename(CellSlot)
+    -> finish()
+}
+
+fn cell_slot(id: u8) -> CellSlot<u8> {
+    CellSlot { id, cell: Cell(id) }
+}
@@ -0,0 +1,35 @@
+#![allow(unused)]
+
+use transtype::pipe;
+
+#[transtype::define]
+struct User {
+    pub id: u64,
+    pub name: String,
+    pub password_hash: String,
+    pub internal_id: u64,
+}
+
+#[transtype::define]
+enum Event {
+    Created { id: u64, internal_id: u64 },
+    Deleted { id: u64 },
+    Unknown,
+}
+
+pipe! {
+    User
+    -> omit(password_hash, internal_id)
+    -> rename(PublicUser)
+    -> finish()
+}
+
+pipe! {
+    Event
+    -> omit(internal_id)
+    -> finish()
+}
+
+fn public(id: u64, name: String) -> (PublicUser, Event) {
+    (PublicUser { id, name }, Event::Created { id })
+}
@@ -0,0 +1,135 @@
+#![allow(unused)]
+
+use std::convert::TryFrom;
+use transtype::{pipe, FieldErrors, Wrapped};
+
+#[transtype::define]
+#[derive(Clone, Debug, PartialEq)]
+struct A {
+    #[transtype(nowrap)]
+    pub id: u64,
+    pub name: String,
+}
+
+pipe! {
+    A
+    -> fork(
+        A={
+            -> finish()
+        }
+        PartialA={
+            -> wrap(Option)
+            -> wrapped()
+            -> finish()
+        }
+        MaybeA={
+            -> wrap(Option)
+            -> try_wrapped()
+            -> finish()
+        }
+    )
+}
+
+pipe! {
+    A
+    -> rename(BoxedA)
+    -> wrap(Box)
+    -> save()
+}
+
+// The saved type remembers that it comes from `A`.
+pipe! {
+    BoxedA
+    -> wrapped()
+    -> finish()
+}
+
+// Fields are followed back to `A` after being renamed.
+pipe! {
+    A
+    -> rename(PrefixedA)
+    -> rename_fields(prefix = "a_")
+    -> wrap(Option)
+    -> wrapped()
+    -> finish()
+}
+
+#[transtype::define]
+#[derive(Clone, Debug, PartialEq)]
+enum E {
+    Created { a: u64 },
+    Moved(u64, u64),
+    Deleted,
+}
+
+pipe! {
+    E
+    -> finish()
+}
+
+// Variants and their fields are followed back to `E` after being renamed.
+pipe! {
+    E
+    -> rename(PE)
+    -> rename_fields(prefix = "p_")
+    -> wrap(Option)
+    -> wrapped()
+    -> finish()
+}
+
+pipe! {
+    E
+    -> rename(RE)
+    -> select_variant(Created as Added, Moved, Deleted as Removed)
+    -> wrap(Box)
+    -> wrapped()
+    -> finish()
+}
+
+fn a() -> A {
+    A {
+        id: 1,
+        name: "a".into(),
+    }
+}
+
+#[test]
+fn wrapped_in_fork() {
+    let partial = PartialA::from(a());
+    assert_eq!(partial.name, Some("a".to_owned()));
+    assert_eq!(partial.unwrap(), a());
+}
+
+#[test]
+fn try_wrapped_in_fork() {
+    let maybe = MaybeA { id: 1, name: None };
+    let errors = A::try_from(maybe).unwrap_err();
+    assert_eq!(errors.fields().collect::<Vec<_>>(), ["name"]);
+}
+
+#[test]
+fn wrapped_after_save() {
+    let boxed = BoxedA::from(a());
+    assert_eq!(*boxed.name, "a");
+    assert_eq!(boxed.unwrap(), a());
+}
+
+#[test]
+fn wrapped_after_rename() {
+    let prefixed = PrefixedA::from(a());
+    assert_eq!(prefixed.a_id, 1);
+    assert_eq!(prefixed.a_name, Some("a".to_owned()));
+    assert_eq!(prefixed.unwrap(), a());
+}
+
+#[test]
+fn wrapped_enum_after_rename() {
+    let created = E::Created { a: 1 };
+    assert_eq!(PE::from(created.clone()), PE::Created { p_a: Some(1) });
+    assert_eq!(PE::Moved(Some(1), Some(2)).unwrap(), E::Moved(1, 2));
+
+    let renamed = RE::from(created.clone());
+    assert_eq!(renamed, RE::Added { a: Box::new(1) });
+    assert_eq!(E::from(renamed), created);
+    assert_eq!(E::from(RE::Removed), E::Deleted);
+}
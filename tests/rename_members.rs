@@ -0,0 +1,74 @@
+#![allow(unused, non_snake_case, non_camel_case_types)]
+
+use transtype::pipe;
+
+#[transtype::define]
+struct Row {
+    pub user_id: u64,
+    pub created_at: u64,
+    pub http_status: u16,
+}
+
+#[transtype::define]
+enum Status {
+    Active,
+    HTTPError,
+    PendingReview,
+}
+
+pipe! {
+    Row
+    -> rename_fields(case = camel)
+    -> rename(WireRow)
+    -> finish()
+}
+
+pipe! {
+    Row
+    -> rename_fields(prefix = "db_")
+    -> rename(DbRow)
+    -> finish()
+}
+
+#[transtype::define]
+struct Kind {
+    pub r#type: u64,
+    pub kind: u64,
+}
+
+pipe! {
+    Kind
+    -> rename_fields(prefix = "r#")
+    -> rename(RawKind)
+    -> finish()
+}
+
+pipe! {
+    Status
+    -> rename_variants(case = screaming_snake, suffix = "_STATUS")
+    -> finish()
+}
+
+fn raw(kind: RawKind) -> u64 {
+    kind.r#type + kind.r#kind
+}
+
+fn renamed() -> (WireRow, DbRow, [Status; 3]) {
+    (
+        WireRow {
+            userId: 0,
+            createdAt: 0,
+            httpStatus: 200,
+        },
+        DbRow {
+            db_user_id: 0,
+            db_created_at: 0,
+            db_http_status: 200,
+        },
+        [
+            Status::ACTIVE_STATUS,
+            Status::HTTP_ERROR_STATUS,
+            Status::PENDING_REVIEW_STATUS,
+        ],
+    )
+}
@@ -0,0 +1,61 @@
+#![allow(unused)]
+
+use transtype::pipe;
+
+#[transtype::define]
+struct User {
+    pub id: u64,
+    pub name: String,
+}
+
+#[transtype::define]
+struct Group {
+    pub id: u64,
+}
+
+macro_rules! patch {
+    ($($name:ident),*) => {$(
+        pipe! {
+            $name
+            -> rename({}Patch)
+            -> wrap(Option)
+            -> finish()
+        }
+    )*};
+}
+
+patch!(User, Group);
+
+pipe! {
+    User
+    -> fork(
+        User={
+            -> finish()
+        }
+        Partial={
+            -> rename({}User)
+            -> finish()
+        }
+    )
+}
+
+pipe! {
+    Group
+    -> rename(Partial{}Data)
+    -> finish()
+}
+
+fn templates(user: User) -> (UserPatch, GroupPatch, PartialUser, PartialGroupData) {
+    (
+        UserPatch {
+            id: Some(user.id),
+            name: None,
+        },
+        GroupPatch { id: None },
+        PartialUser {
+            id: user.id,
+            name: user.name,
+        },
+        PartialGroupData { id: 0 },
+    )
+}
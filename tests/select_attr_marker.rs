@@ -0,0 +1,57 @@
+#![allow(unused)]
+
+use transtype::pipe;
+
+#[transtype::define]
+struct A {
+    #[readonly]
+    pub id: u64,
+    #[serde(default, skip)]
+    pub cache: Vec<u8>,
+    #[secret]
+    pub password: String,
+    pub name: String,
+}
+
+macro_rules! strip_markers {
+    (
+        data={
+            struct $name:ident {
+                $(
+                    $(#[$attr:meta])*
+                    $vis:vis $field:ident: $ty:ty
+                ),* $(,)?
+            }
+        }
+        args={}
+        rest=$rest:tt
+    ) => {
+        ::transtype::transform! {
+            @consume
+            data={
+                struct $name {
+                    $($vis $field: $ty,)*
+                }
+            }
+            rest=$rest
+        }
+    };
+}
+
+pipe! {
+    A
+    -> select(#[readonly] strip)
+    -> rename(ReadonlyA)
+    -> finish()
+}
+
+pipe! {
+    A
+    -> omit(#[secret], #[serde(skip)])
+    -> rename(PublicA)
+    -> strip_markers()
+}
+
+fn markers(name: String) -> (ReadonlyA, PublicA) {
+    (ReadonlyA { id: 0 }, PublicA { id: 0, name })
+}
@@ -0,0 +1,64 @@
+#![allow(unused)]
+
+use transtype::pipe;
+
+#[transtype::define]
+#[derive_a(Clone)]
+#[derive_b(Debug)]
+struct A {
+    pub id: u64,
+    pub user_name: String,
+    pub user_email: String,
+    pub group_id: u64,
+    pub old_value: u64,
+    pub old_count: usize,
+}
+
+pipe! {
+    A
+    -> select(user_*, *_id)
+    -> select_attr(derive_* as derive)
+    -> rename(UserA)
+    -> finish()
+}
+
+pipe! {
+    A
+    -> select(id, old_* as new_*, user_* as *)
+    -> select_attr()
+    -> rename(RenamedA)
+    -> finish()
+}
+
+fn globbed(user: UserA) -> RenamedA {
+    let UserA {
+        user_name,
+        user_email,
+        group_id,
+    } = user.clone();
+    let _ = format!("{user:?}");
+    RenamedA {
+        id: 0,
+        new_value: 0,
+        new_count: 0,
+        name: user_name,
+        email: user_email,
+    }
+}
+
+#[transtype::define]
+struct Keyword {
+    pub r#type: u64,
+    pub r#match: String,
+}
+
+pipe! {
+    Keyword
+    -> select(* as *)
+    -> rename(RawKeyword)
+    -> finish()
+}
+
+fn raw(keyword: RawKeyword) -> (u64, String) {
+    (keyword.r#type, keyword.r#match)
+}
@@ -0,0 +1,69 @@
+#![allow(unused)]
+
+use std::{collections::HashMap, marker::PhantomData};
+use transtype::pipe;
+
+#[transtype::define]
+struct A {
+    pub id: u64,
+    pub name: Option<String>,
+    pub tags: Vec<String>,
+    pub extra: HashMap<String, String>,
+    pub parent: std::option::Option<u64>,
+    pub marker: PhantomData<u8>,
+}
+
+#[transtype::define]
+struct B(pub u64, pub Option<u64>, pub PhantomData<()>);
+
+pipe! {
+    A
+    -> select(: Option<_>)
+    -> rename(OptionalA)
+    -> finish()
+}
+
+pipe! {
+    A
+    -> select(id, : Vec<_> | HashMap<_, _>)
+    -> rename(CollectionA)
+    -> finish()
+}
+
+pipe! {
+    A
+    -> omit(: Option<_>)
+    -> rename(RequiredA)
+    -> finish()
+}
+
+pipe! {
+    B
+    -> omit(: PhantomData<_>)
+    -> finish()
+}
+
+fn selected(
+    tags: Vec<String>,
+    extra: HashMap<String, String>,
+    marker: PhantomData<u8>,
+) -> (OptionalA, CollectionA, RequiredA, B) {
+    (
+        OptionalA {
+            name: None,
+            parent: None,
+        },
+        CollectionA {
+            id: 0,
+            tags: tags.clone(),
+            extra: extra.clone(),
+        },
+        RequiredA {
+            id: 0,
+            tags,
+            extra,
+            marker,
+        },
+        B(0, None),
+    )
+}
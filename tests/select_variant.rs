@@ -0,0 +1,38 @@
+#![allow(unused)]
+
+use transtype::pipe;
+
+#[transtype::define]
+#[derive(Debug)]
+enum InternalEvent {
+    Created { id: u64 },
+    Updated { id: u64, fields: Vec<String> },
+    Deleted(u64),
+    #[internal]
+    Rebalanced,
+    Internal,
+}
+
+pipe! {
+    InternalEvent
+    -> select_variant(Created, Updated as Modified, Deleted)
+    -> rename(PublicEvent)
+    -> finish()
+}
+
+pipe! {
+    InternalEvent
+    -> omit_variant(Internal, #[internal])
+    -> rename(VisibleEvent)
+    -> finish()
+}
+
+fn events() -> (PublicEvent, VisibleEvent) {
+    (
+        PublicEvent::Modified {
+            id: 0,
+            fields: Vec::new(),
+        },
+        VisibleEvent::Deleted(0),
+    )
+}
@@ -0,0 +1,150 @@
+#![allow(unused)]
+
+use std::convert::TryFrom;
+use transtype::{pipe, FieldErrors, Missing};
+
+#[derive(Debug, PartialEq)]
+struct FieldError(&'static str);
+
+#[transtype::define]
+#[derive(Debug, PartialEq)]
+struct A {
+    #[transtype(nowrap)]
+    pub id: u64,
+    pub name: String,
+    pub age: u8,
+}
+
+pipe! {
+    A
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(PartialA)
+    -> wrap(Option)
+    -> try_wrapped(A)
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(ParsedA)
+    -> wrap(Result<_, FieldError>)
+    -> try_wrapped(A)
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(MixedA)
+    -> wrap(Option, only = [name])
+    -> wrap(Box, only = [age])
+    -> try_wrapped(A)
+    -> finish()
+}
+
+// Nothing may fail, so both generate `From` only once.
+pipe! {
+    A
+    -> rename(BoxedA)
+    -> wrap(Box)
+    -> wrapped(A)
+    -> try_wrapped(A)
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(SharedA)
+    -> wrap(std::rc::Rc)
+    -> try_wrapped(A)
+    -> finish()
+}
+
+#[test]
+fn convert_filled() {
+    let a = PartialA {
+        id: 1,
+        name: Some("a".into()),
+        age: Some(2),
+    };
+    let expected = A {
+        id: 1,
+        name: "a".into(),
+        age: 2,
+    };
+    assert_eq!(A::try_from(a), Ok(expected));
+}
+
+#[test]
+fn collect_missing_fields() {
+    let a = PartialA {
+        id: 1,
+        name: None,
+        age: None,
+    };
+    let errors: FieldErrors<Missing> = A::try_from(a).unwrap_err();
+    assert_eq!(errors.fields().collect::<Vec<_>>(), ["name", "age"]);
+}
+
+#[test]
+fn collect_errors() {
+    let a = ParsedA {
+        id: 1,
+        name: Err(FieldError("empty")),
+        age: Err(FieldError("negative")),
+    };
+    let errors = A::try_from(a).unwrap_err().into_inner();
+    assert_eq!(
+        errors,
+        [
+            ("name", FieldError("empty")),
+            ("age", FieldError("negative"))
+        ]
+    );
+}
+
+#[test]
+fn mix_wrappers() {
+    let a = MixedA {
+        id: 1,
+        name: Some("a".into()),
+        age: Box::new(2),
+    };
+    let expected = A {
+        id: 1,
+        name: "a".into(),
+        age: 2,
+    };
+    assert_eq!(A::try_from(a), Ok(expected));
+    let a = MixedA {
+        id: 1,
+        name: None,
+        age: Box::new(2),
+    };
+    let errors = A::try_from(a).unwrap_err();
+    assert_eq!(errors.fields().collect::<Vec<_>>(), ["name"]);
+}
+
+#[test]
+fn convert_infallible() {
+    let expected = A {
+        id: 1,
+        name: "a".into(),
+        age: 2,
+    };
+    let a = BoxedA {
+        id: 1,
+        name: Box::new("a".into()),
+        age: Box::new(2),
+    };
+    assert_eq!(A::from(a), expected);
+    let a = SharedA {
+        id: 1,
+        name: "a".to_owned().into(),
+        age: 2.into(),
+    };
+    assert_eq!(A::from(a), expected);
+}
@@ -0,0 +1,67 @@
+#![allow(unused)]
+
+use std::convert::TryFrom;
+use transtype::{pipe, FieldErrors};
+
+#[transtype::define]
+#[derive(Default)]
+struct PartialA {
+    pub a: Option<String>,
+    pub b: std::option::Option<usize>,
+    pub c: Vec<u8>,
+}
+
+pipe! {
+    PartialA
+    -> finish()
+}
+
+pipe! {
+    PartialA
+    -> rename(A)
+    -> unwrap(Option, from = PartialA)
+    -> finish()
+}
+
+#[transtype::define]
+struct PartialB<T>(Option<T>, u8);
+
+pipe! {
+    PartialB
+    -> finish()
+}
+
+pipe! {
+    PartialB
+    -> rename(B)
+    -> unwrap(Option, from = PartialB<T>)
+    -> finish()
+}
+
+#[test]
+fn unwrap_fields() {
+    let a = A::try_from(PartialA {
+        a: Some("a".into()),
+        b: Some(1),
+        c: vec![2],
+    })
+    .unwrap();
+    let A { a, b, c }: A = a;
+    assert_eq!((a.as_str(), b, c), ("a", 1, vec![2]));
+
+    let b = B::try_from(PartialB(Some('b'), 1)).unwrap();
+    assert_eq!((b.0, b.1), ('b', 1));
+}
+
+#[test]
+fn report_missing_fields() {
+    let errors: FieldErrors = A::try_from(PartialA::default()).err().unwrap();
+    assert_eq!(errors.fields().collect::<Vec<_>>(), ["a", "b"]);
+    assert_eq!(
+        errors.to_string(),
+        "failed to unwrap `a` (missing value), `b` (missing value)"
+    );
+
+    let errors = B::try_from(PartialB::<()>(None, 1)).err().unwrap();
+    assert_eq!(errors.fields().collect::<Vec<_>>(), ["0"]);
+}
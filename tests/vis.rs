@@ -0,0 +1,50 @@
+#![allow(unused)]
+
+use transtype::pipe;
+
+#[transtype::define]
+pub struct A {
+    pub id: u64,
+    name: String,
+    secret: String,
+}
+
+pipe! {
+    A
+    -> rename(MutableA)
+    -> vis(pub(crate), fields: pub(crate), field secret:)
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(PublicA)
+    -> vis(fields: pub)
+    -> finish()
+}
+
+mod inner {
+    use transtype::pipe;
+
+    #[transtype::define]
+    struct B {
+        b: u64,
+    }
+
+    pipe! {
+        B
+        -> vis(pub, fields: pub)
+        -> finish()
+    }
+}
+
+fn visible(a: PublicA) -> (MutableA, inner::B) {
+    (
+        MutableA {
+            id: a.id,
+            name: a.name,
+            secret: a.secret,
+        },
+        inner::B { b: 0 },
+    )
+}
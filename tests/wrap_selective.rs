@@ -0,0 +1,78 @@
+#![allow(unused)]
+
+use transtype::{pipe, Wrapped};
+
+#[transtype::define]
+#[derive(Clone, Debug, PartialEq)]
+struct A {
+    #[transtype(nowrap)]
+    pub id: u64,
+    pub a: String,
+    pub b: usize,
+    pub c: Option<u8>,
+}
+
+pipe! {
+    A
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(OnlyA)
+    -> wrap(Option, only = [a, c])
+    -> wrapped(A)
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(ExceptA)
+    -> wrap(Option, except = [b])
+    -> wrapped(A)
+    -> finish()
+}
+
+#[transtype::define]
+struct B(u64, #[transtype(nowrap)] String, bool);
+
+pipe! {
+    B
+    -> finish()
+}
+
+pipe! {
+    B
+    -> rename(WrappedB)
+    -> wrap(Option, except = [2])
+    -> wrapped(B)
+    -> finish()
+}
+
+#[test]
+fn wrap_selected_fields() {
+    let a = OnlyA {
+        id: 1,
+        a: Some("a".into()),
+        b: 2,
+        c: Some(3),
+    };
+    let expected = A {
+        id: 1,
+        a: "a".into(),
+        b: 2,
+        c: Some(3),
+    };
+    assert_eq!(a.unwrap(), expected);
+
+    let a = ExceptA {
+        id: 1,
+        a: Some("a".into()),
+        b: 2,
+        c: Some(3),
+    };
+    assert_eq!(a.unwrap(), expected);
+
+    let B(a, b, c) = WrappedB(Some(1), "b".into(), true).unwrap();
+    assert_eq!((a, b.as_str(), c), (1, "b", true));
+}
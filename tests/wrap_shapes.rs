@@ -0,0 +1,126 @@
+#![allow(unused)]
+
+use std::convert::TryFrom;
+use transtype::{pipe, Wrapped};
+
+#[transtype::define]
+#[derive(Debug, PartialEq)]
+enum Event {
+    Created { id: u64, name: String },
+    Moved(u64, (i32, i32)),
+    Deleted,
+}
+
+pipe! {
+    Event
+    -> finish()
+}
+
+pipe! {
+    Event
+    -> rename(PartialEvent)
+    -> wrap(Option, except = [id])
+    -> finish()
+}
+
+#[transtype::define]
+#[derive(Debug, PartialEq)]
+struct A<'a> {
+    pub point: (i32, i32),
+    pub name: &'a str,
+    pub bytes: [u8; 2],
+    pub tags: &'a [String],
+}
+
+pipe! {
+    A
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(WrappedA)
+    -> wrap(Option)
+    -> wrapped(A<'a>)
+    -> finish()
+}
+
+#[transtype::define]
+#[derive(Clone, Debug, PartialEq)]
+struct B {
+    pub id: u64,
+    pub name: String,
+}
+
+pipe! {
+    B
+    -> fork(
+        B={
+            -> finish()
+        }
+        // Every wrapper added to a field is removed one by one.
+        NestedB={
+            -> wrap(Box)
+            -> wrap(Option)
+            -> wrapped()
+            -> finish()
+        }
+        MaybeNestedB={
+            -> wrap(Box)
+            -> wrap(Option, except = [id])
+            -> try_wrapped()
+            -> finish()
+        }
+    )
+}
+
+fn partial_events() -> [PartialEvent; 3] {
+    [
+        PartialEvent::Created { id: 1, name: None },
+        PartialEvent::Moved(Some(1), Some((0, 0))),
+        PartialEvent::Deleted,
+    ]
+}
+
+#[test]
+fn wrap_non_path_fields() {
+    let tags = vec!["a".to_string()];
+    let a = WrappedA {
+        point: Some((1, 2)),
+        name: Some("a"),
+        bytes: Some([3, 4]),
+        tags: Some(&tags),
+    };
+    let expected = A {
+        point: (1, 2),
+        name: "a",
+        bytes: [3, 4],
+        tags: &tags,
+    };
+    assert_eq!(a.unwrap(), expected);
+}
+
+#[test]
+fn wrap_nested() {
+    let b = B {
+        id: 1,
+        name: "b".into(),
+    };
+    let nested = NestedB::from(b.clone());
+    assert_eq!(nested.name, Some(Box::new("b".to_owned())));
+    assert_eq!(nested.unwrap(), b);
+
+    let maybe = MaybeNestedB {
+        id: Box::new(1),
+        name: Some(Box::new("b".into())),
+    };
+    assert_eq!(B::try_from(maybe).unwrap(), b);
+    let maybe = MaybeNestedB {
+        id: Box::new(1),
+        name: None,
+    };
+    assert_eq!(
+        B::try_from(maybe).unwrap_err().fields().collect::<Vec<_>>(),
+        ["name"]
+    );
+}
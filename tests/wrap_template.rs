@@ -0,0 +1,108 @@
+#![allow(unused)]
+
+use std::sync::Arc;
+use transtype::{pipe, Wrapped};
+
+#[derive(Debug, PartialEq)]
+struct FieldError;
+
+#[transtype::define]
+#[derive(Debug, PartialEq)]
+struct A {
+    pub a: String,
+    pub b: Option<u8>,
+    pub c: std::option::Option<u16>,
+}
+
+pipe! {
+    A
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(ResultA)
+    -> wrap(Result<_, FieldError>)
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(BoxedA)
+    -> wrap(Box)
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(ArcA)
+    -> wrap(std::sync::Arc<_>)
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(RefA)
+    -> wrap(&'a _)
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(ArrayA)
+    -> wrap([_; 2])
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(OptionA)
+    -> wrap(std::option::Option)
+    -> wrapped(A)
+    -> finish()
+}
+
+fn wrapped<'a>(a: &'a String) -> (ResultA, BoxedA, ArcA, RefA<'a>, ArrayA) {
+    (
+        ResultA {
+            a: Err(FieldError),
+            b: Ok(None),
+            c: Ok(None),
+        },
+        BoxedA {
+            a: Box::new(a.clone()),
+            b: Box::new(None),
+            c: Box::new(None),
+        },
+        ArcA {
+            a: Arc::new(a.clone()),
+            b: Arc::new(None),
+            c: Arc::new(None),
+        },
+        RefA {
+            a,
+            b: &None,
+            c: &None,
+        },
+        ArrayA {
+            a: [a.clone(), a.clone()],
+            b: [None; 2],
+            c: [None; 2],
+        },
+    )
+}
+
+#[test]
+fn skip_wrapped_fields() {
+    let a = OptionA {
+        a: Some("a".into()),
+        b: None,
+        c: Some(1),
+    };
+    let expected = A {
+        a: "a".into(),
+        b: None,
+        c: Some(1),
+    };
+    assert_eq!(a.unwrap(), expected);
+}
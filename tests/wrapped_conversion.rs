@@ -0,0 +1,165 @@
+#![allow(unused)]
+
+use std::convert::TryFrom;
+use std::rc::Rc;
+use transtype::{pipe, WrapInto, Wrapped};
+
+#[transtype::define]
+#[derive(Clone, Debug, PartialEq)]
+struct A {
+    #[transtype(nowrap)]
+    pub id: u64,
+    pub name: String,
+}
+
+pipe! {
+    A
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(PartialA)
+    -> wrap(Option)
+    -> wrapped(A)
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(SharedA)
+    -> wrap(Rc)
+    -> wrapped(A)
+    -> finish()
+}
+
+#[transtype::define]
+#[derive(Clone, Debug, PartialEq)]
+enum Event {
+    Created { id: u64, name: String },
+    Moved(u64, (i32, i32)),
+    Deleted,
+}
+
+pipe! {
+    Event
+    -> finish()
+}
+
+pipe! {
+    Event
+    -> rename(PartialEvent)
+    -> wrap(Option, except = [id])
+    -> wrapped(Event)
+    -> finish()
+}
+
+mod m {
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Plain {
+        pub id: u64,
+        pub name: String,
+    }
+}
+
+// `m::Plain` isn't defined by transtype, it's converted by field names.
+#[transtype::define]
+struct Mirror {
+    #[transtype(nowrap)]
+    pub id: u64,
+    pub name: String,
+}
+
+pipe! {
+    Mirror
+    -> rename(PartialPlain)
+    -> wrap(Option)
+    -> wrapped(m::Plain)
+    -> finish()
+}
+
+pipe! {
+    Mirror
+    -> rename(MaybePlain)
+    -> wrap(Option)
+    -> try_wrapped(m::Plain)
+    -> finish()
+}
+
+pipe! {
+    Mirror
+    -> rename(PlainName)
+    -> select(name)
+    -> from(&m::Plain)
+    -> finish()
+}
+
+fn a() -> A {
+    A {
+        id: 1,
+        name: "a".into(),
+    }
+}
+
+#[test]
+fn convert_struct() {
+    let partial = PartialA::from(a());
+    assert_eq!(
+        partial,
+        PartialA {
+            id: 1,
+            name: Some("a".into()),
+        }
+    );
+    let partial: PartialA = a().wrap_into();
+    assert_eq!(partial.unwrap(), a());
+
+    let shared: SharedA = a().wrap_into();
+    assert_eq!(A::from(shared), a());
+}
+
+#[test]
+fn convert_enum() {
+    let events = [
+        Event::Created {
+            id: 1,
+            name: "a".into(),
+        },
+        Event::Moved(2, (3, 4)),
+        Event::Deleted,
+    ];
+    let partial = events.clone().map(PartialEvent::from);
+    assert_eq!(
+        partial,
+        [
+            PartialEvent::Created {
+                id: 1,
+                name: Some("a".into()),
+            },
+            PartialEvent::Moved(Some(2), Some((3, 4))),
+            PartialEvent::Deleted,
+        ]
+    );
+    assert_eq!(partial.map(Wrapped::unwrap), events);
+}
+
+#[test]
+fn convert_plain_type() {
+    let plain = m::Plain {
+        id: 1,
+        name: "a".into(),
+    };
+    let partial = PartialPlain::from(plain.clone());
+    assert_eq!(partial.name, Some("a".to_owned()));
+    assert_eq!(partial.unwrap(), plain);
+
+    let maybe = MaybePlain { id: 1, name: None };
+    assert_eq!(
+        m::Plain::try_from(maybe)
+            .unwrap_err()
+            .fields()
+            .collect::<Vec<_>>(),
+        ["name"]
+    );
+    assert_eq!(PlainName::from(&plain).name, plain.name);
+}
@@ -0,0 +1,69 @@
+#![allow(unused)]
+
+use transtype::{pipe, Wrapped};
+
+#[transtype::define]
+#[derive(Debug, PartialEq)]
+enum Event<T> {
+    Created { id: u64, name: String },
+    Moved(u64, (i32, i32)),
+    Tagged(T),
+    Deleted,
+}
+
+pipe! {
+    Event
+    -> finish()
+}
+
+pipe! {
+    Event
+    -> rename(PartialEvent)
+    -> wrap(Option, except = [id])
+    -> wrapped(Event<T>)
+    -> finish()
+}
+
+pipe! {
+    Event
+    -> rename(CreatedEvent)
+    -> select_variant(Created, Tagged)
+    -> wrap(Box)
+    -> wrapped(Event<T>)
+    -> finish()
+}
+
+#[test]
+fn unwrap_variants() {
+    let events: [PartialEvent<&str>; 4] = [
+        PartialEvent::Created {
+            id: 1,
+            name: Some("a".into()),
+        },
+        PartialEvent::Moved(Some(1), Some((2, 3))),
+        PartialEvent::Tagged(Some("t")),
+        PartialEvent::Deleted,
+    ];
+    let expected = [
+        Event::Created {
+            id: 1,
+            name: "a".into(),
+        },
+        Event::Moved(1, (2, 3)),
+        Event::Tagged("t"),
+        Event::Deleted,
+    ];
+    assert_eq!(events.map(Wrapped::unwrap), expected);
+
+    let event = CreatedEvent::<()>::Created {
+        id: Box::new(1),
+        name: Box::new("a".into()),
+    };
+    assert_eq!(
+        event.unwrap(),
+        Event::Created {
+            id: 1,
+            name: "a".into()
+        }
+    );
+}
@@ -0,0 +1,120 @@
+#![allow(unused)]
+
+use std::{borrow::Cow, cell::RefCell, rc::Rc, sync::Mutex};
+use transtype::{pipe, Identity, Wrapped, Wrapper};
+
+#[derive(Wrapper)]
+struct Sensitive<T>(T);
+
+#[derive(Wrapper)]
+struct Email {
+    value: String,
+}
+
+#[transtype::define]
+#[derive(Debug, PartialEq)]
+struct A {
+    pub name: String,
+    pub age: u8,
+}
+
+pipe! {
+    A
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(SensitiveA)
+    -> derive(-Debug, -PartialEq)
+    -> wrap(Sensitive)
+    -> wrapped(A)
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(SharedA)
+    -> derive(-Debug, -PartialEq)
+    -> wrap(Rc)
+    -> wrapped(A)
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(LockedA)
+    -> derive(-Debug, -PartialEq)
+    -> wrap(Mutex)
+    -> wrapped(A)
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(IdentityA)
+    -> wrap(Identity)
+    -> wrapped(A)
+    -> finish()
+}
+
+pipe! {
+    A
+    -> rename(CowA)
+    -> derive(-Debug, -PartialEq)
+    -> wrap(Cow<'static, _>)
+    -> wrapped(A)
+    -> finish()
+}
+
+fn a() -> A {
+    A {
+        name: "a".into(),
+        age: 1,
+    }
+}
+
+#[test]
+fn derive_wrapper() {
+    let email = Email::wrap("a@b.c".to_string());
+    assert_eq!(Wrapper::unwrap(email), "a@b.c");
+
+    let a = SensitiveA {
+        name: Sensitive("a".into()),
+        age: Sensitive(1),
+    };
+    assert_eq!(Wrapped::unwrap(a), self::a());
+}
+
+#[test]
+fn std_wrappers() {
+    let name = Rc::new("a".to_string());
+    let a = SharedA {
+        name: name.clone(),
+        age: Rc::new(1),
+    };
+    assert_eq!(Wrapped::unwrap(a), self::a());
+
+    let a = LockedA {
+        name: Mutex::new("a".into()),
+        age: Mutex::new(1),
+    };
+    assert_eq!(Wrapped::unwrap(a), self::a());
+
+    let a = CowA {
+        name: Cow::Owned("a".into()),
+        age: Cow::Borrowed(&1),
+    };
+    assert_eq!(Wrapped::unwrap(a), self::a());
+
+    let a = IdentityA {
+        name: Identity("a".into()),
+        age: Identity(1),
+    };
+    assert_eq!(Wrapped::unwrap(a), self::a());
+
+    let name: Cow<str> = Wrapper::wrap("a".to_string());
+    assert_eq!(Wrapper::<String>::unwrap(name), "a");
+    assert_eq!(Wrapper::<u8>::unwrap(RefCell::new(1)), 1);
+    assert_eq!(Wrapper::unwrap(Identity(1)), 1);
+}